@@ -3,6 +3,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=proto/graphql.proto");
     println!("cargo:rerun-if-changed=proto/greeter.proto");
     println!("cargo:rerun-if-changed=proto/federation_example.proto");
+    println!("cargo:rerun-if-changed=proto/recursive.proto");
+    println!("cargo:rerun-if-changed=proto/fixtures");
+    println!("cargo:rerun-if-changed=proto/google");
     println!("cargo:rerun-if-changed=build.rs");
 
     // Use src/generated directory for generated files
@@ -36,5 +39,42 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .file_descriptor_set_path(generated_dir.join("federation_example_descriptor.bin"))
         .compile_protos(&["proto/federation_example.proto"], &proto_paths)?;
 
+    // Recursive message fixture used by the schema tests
+    tonic_build::configure()
+        .out_dir(&generated_dir)
+        .file_descriptor_set_path(generated_dir.join("recursive_descriptor.bin"))
+        .compile_protos(&["proto/recursive.proto"], &proto_paths)?;
+
+    // Fixtures used by the tests, compiled once into OUT_DIR, where the tests include
+    // their descriptors and Rust code from.
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+    tonic_build::configure()
+        .out_dir(&out_dir)
+        .file_descriptor_set_path(out_dir.join("fixtures_descriptor.bin"))
+        .compile_protos(
+            &[
+                "proto/fixtures/types.proto",
+                "proto/fixtures/annotations.proto",
+                "proto/fixtures/operations.proto",
+                "proto/fixtures/inventory.proto",
+            ],
+            &proto_paths,
+        )?;
+
+    // Two revisions of the same API for the schema diff tests, in their own directories
+    // so they do not overwrite each other
+    for revision in ["v1", "v2"] {
+        let revision_dir = format!("proto/fixtures/compat/{revision}");
+        let code_dir = out_dir.join(format!("compat_{revision}"));
+        std::fs::create_dir_all(&code_dir)?;
+        tonic_build::configure()
+            .out_dir(&code_dir)
+            .file_descriptor_set_path(code_dir.join("catalog_descriptor.bin"))
+            .compile_protos(
+                &[format!("{revision_dir}/catalog.proto")],
+                &[revision_dir.as_str(), "proto", &proto_include],
+            )?;
+    }
+
     Ok(())
 }
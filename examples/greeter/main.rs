@@ -91,7 +91,6 @@ fn print_examples(addr: SocketAddr) {
     println!("WebSocket endpoint: ws://{}/graphql/ws", addr);
     // curl (query): curl -X POST http://127.0.0.1:8888/graphql -H 'content-type: application/json' -d '{"query":"{ hello(name:\"GraphQL\"){ message } }"}'
     // curl (mutation): curl -X POST http://127.0.0.1:8888/graphql -H 'content-type: application/json' -d '{"query":"mutation { updateGreeting(input:{ name:\"GraphQL\", salutation:\"Howdy\" }) { message } }"}'
-    // curl (resolver): curl -X POST http://127.0.0.1:8888/graphql -H 'content-type: application/json' -d '{"query":"{ user(id:\"demo\"){ id displayName trusted } }"}'
     // subscription (graphql-transport-ws):
     //   websocat -H="Sec-WebSocket-Protocol: graphql-transport-ws" --protocol graphql-transport-ws ws://127.0.0.1:8888/graphql/ws
     //   # then type/paste:
@@ -105,10 +104,12 @@ fn print_examples(addr: SocketAddr) {
     println!(
         "  mutation {{ updateGreeting(input:{{ name:\"GraphQL\", salutation:\"Howdy\" }}) {{ message }} }}"
     );
+    println!(
+        "  mutation {{ updateUser(input:{{ id:\"demo\", displayName:\"Dee\", trusted:true }}) {{ id displayName }} }}"
+    );
     println!(
         "  subscription {{ streamHello(name:\"GraphQL\") {{ message meta {{ correlationId }} }} }}"
     );
-    println!("  query {{ user(id:\"demo\") {{ id displayName trusted }} }}");
     println!("  # Upload (multipart): see README for the curl example");
     println!("  # Multi-upload (multipart): see README for the curl example");
 }
@@ -218,6 +219,18 @@ impl Greeter for ExampleGreeter {
         Err(Status::not_found(format!("user {} not found", req.id)))
     }
 
+    async fn update_user(&self, request: Request<User>) -> Result<Response<User>, Status> {
+        let user = request.into_inner();
+        if user.id.is_empty() {
+            return Err(Status::invalid_argument("user id is required"));
+        }
+        self.users
+            .write()
+            .await
+            .insert(user.id.clone(), user.clone());
+        Ok(Response::new(user))
+    }
+
     async fn upload_avatar(
         &self,
         request: Request<UploadAvatarRequest>,
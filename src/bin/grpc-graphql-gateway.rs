@@ -0,0 +1,104 @@
+//! Command-line tools for the gateway.
+//!
+//! `diff` builds the GraphQL schema for an old and a new descriptor set (or reads `.graphql`
+//! SDL files) and reports breaking, dangerous and safe changes. It exits with status 1 when
+//! breaking changes are found, so it can gate CI:
+//!
+//! ```text
+//! grpc-graphql-gateway diff old_descriptor.bin new_descriptor.bin
+//! ```
+
+use grpc_graphql_gateway::{GraphqlNaming, SchemaBuilder, SchemaChange, SchemaDiff, SchemaSource};
+
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: grpc-graphql-gateway diff [--graphql-naming] <OLD> <NEW>
+
+Compare the GraphQL schemas generated for two descriptor sets and report
+breaking, dangerous and safe changes. <OLD> and <NEW> are descriptor sets
+(protoc --descriptor_set_out) or SDL files ending in .graphql/.graphqls.
+
+Options:
+  --graphql-naming  Build schemas with GraphqlNaming instead of proto names
+
+Exit status: 0 without breaking changes, 1 with breaking changes, 2 on errors.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.split_first() {
+        Some((command, rest)) if command == "diff" => match diff(rest) {
+            Ok(false) => ExitCode::SUCCESS,
+            Ok(true) => ExitCode::from(1),
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::from(2)
+            }
+        },
+        Some((command, _)) if matches!(command.as_str(), "help" | "-h" | "--help") => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Print the report and return whether it contains breaking changes.
+fn diff(args: &[String]) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut graphql_naming = false;
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--graphql-naming" => graphql_naming = true,
+            flag if flag.starts_with('-') => {
+                return Err(format!("unknown option {flag}\n\n{USAGE}").into())
+            }
+            path => paths.push(path),
+        }
+    }
+    let [old, new] = paths[..] else {
+        return Err(format!("expected <OLD> and <NEW>\n\n{USAGE}").into());
+    };
+
+    let diff = SchemaDiff::between(
+        load_schema(old, graphql_naming)?,
+        load_schema(new, graphql_naming)?,
+    )?;
+    if diff.is_empty() {
+        println!("No schema changes.");
+    }
+    print_section("Breaking changes", diff.breaking());
+    print_section("Dangerous changes", diff.dangerous());
+    print_section("Safe changes", diff.safe());
+
+    Ok(diff.has_breaking_changes())
+}
+
+fn load_schema(
+    path: &str,
+    graphql_naming: bool,
+) -> Result<SchemaSource, Box<dyn std::error::Error>> {
+    if path.ends_with(".graphql") || path.ends_with(".graphqls") {
+        return Ok(SchemaSource::Sdl(std::fs::read_to_string(path)?));
+    }
+
+    let mut builder = SchemaBuilder::new().with_descriptor_set_file(path)?;
+    if graphql_naming {
+        builder = builder.with_naming_strategy(GraphqlNaming::new());
+    }
+    Ok(builder.into())
+}
+
+fn print_section<'a>(title: &str, changes: impl Iterator<Item = &'a SchemaChange>) {
+    let changes: Vec<_> = changes.collect();
+    if changes.is_empty() {
+        return;
+    }
+    println!("{title} ({}):", changes.len());
+    for change in changes {
+        println!("  {change}");
+    }
+}
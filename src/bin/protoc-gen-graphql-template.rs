@@ -331,7 +331,7 @@ fn render_template(
     buf.push_str("        mutations = describe(MUTATIONS),\n");
     buf.push_str("        subscriptions = describe(SUBSCRIPTIONS),\n");
     buf.push_str("    );\n\n");
-    buf.push_str("    // NOTE: Resolver entries are listed above; the runtime currently warns that they are not implemented.\n");
+    buf.push_str("    // NOTE: Resolver entries are internal; they back nested `graphql.field.resolver` fields and are not exposed on Query.\n");
     buf.push_str("    gateway_builder()?\n");
     buf.push_str("        .serve(\"0.0.0.0:8888\")\n");
     buf.push_str("        .await\n");
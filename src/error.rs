@@ -11,9 +11,9 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// including gRPC errors, schema errors, and runtime errors.
 #[derive(Error, Debug)]
 pub enum Error {
-    /// gRPC transport errors
+    /// gRPC status errors, boxed to keep `Result`s small
     #[error("gRPC error: {0}")]
-    Grpc(#[from] tonic::Status),
+    Grpc(#[from] Box<tonic::Status>),
 
     /// gRPC transport errors
     #[error("gRPC transport error: {0}")]
@@ -60,6 +60,12 @@ pub enum Error {
     Other(#[from] anyhow::Error),
 }
 
+impl From<tonic::Status> for Error {
+    fn from(status: tonic::Status) -> Self {
+        Error::Grpc(Box::new(status))
+    }
+}
+
 impl Error {
     /// Convert error to GraphQL error format
     pub fn to_graphql_error(&self) -> GraphQLError {
@@ -5,6 +5,7 @@
 
 use crate::error::{Error, Result};
 use crate::graphql::GraphqlEntity;
+use crate::naming::{NamingStrategy, ProtoNaming};
 use async_graphql::dynamic::{Field, FieldFuture, FieldValue, InputValue, Object, TypeRef};
 use async_graphql::{Name, Value as GqlValue};
 use prost::Message;
@@ -46,6 +47,15 @@ impl FederationConfig {
     pub fn from_descriptor_pool(
         pool: &DescriptorPool,
         entity_ext: &ExtensionDescriptor,
+    ) -> Result<Self> {
+        Self::from_descriptor_pool_with_naming(pool, entity_ext, &ProtoNaming)
+    }
+
+    /// Extract federation configuration, naming entity types with `naming`.
+    pub fn from_descriptor_pool_with_naming(
+        pool: &DescriptorPool,
+        entity_ext: &ExtensionDescriptor,
+        naming: &dyn NamingStrategy,
     ) -> Result<Self> {
         let mut config = Self::new();
 
@@ -56,7 +66,7 @@ impl FederationConfig {
                     continue; // Skip messages without keys
                 }
 
-                let type_name = message.full_name().replace('.', "_");
+                let type_name = naming.type_name(message.full_name(), message.package_name());
                 let keys: Vec<Vec<String>> = entity_opts
                     .keys
                     .iter()
@@ -3,11 +3,14 @@
 use crate::error::{GraphQLError, Result};
 use crate::grpc_client::{GrpcClient, GrpcClientPool};
 use crate::middleware::Middleware;
+use crate::naming::NamingStrategy;
+use crate::reload::SchemaHandle;
 use crate::runtime::ServeMux;
-use crate::schema::{DynamicSchema, SchemaBuilder};
+use crate::schema::{DynamicSchema, MapRepresentation, SchemaBuilder};
 use axum::Router;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Main Gateway struct - entry point for the library
 ///
@@ -31,7 +34,7 @@ use std::sync::Arc;
 pub struct Gateway {
     mux: ServeMux,
     client_pool: GrpcClientPool,
-    schema: DynamicSchema,
+    schema: SchemaHandle,
 }
 
 impl Gateway {
@@ -45,8 +48,13 @@ impl Gateway {
         &self.mux
     }
 
-    /// Access the built GraphQL schema
-    pub fn schema(&self) -> &DynamicSchema {
+    /// The GraphQL schema currently being served
+    pub fn schema(&self) -> DynamicSchema {
+        self.schema.current()
+    }
+
+    /// Handle for reloading the schema while the gateway runs; see [`SchemaHandle`].
+    pub fn schema_handle(&self) -> &SchemaHandle {
         &self.schema
     }
 
@@ -129,6 +137,8 @@ impl GatewayBuilder {
     /// Provide a protobuf descriptor set (bytes)
     ///
     /// This is typically loaded from a file generated by `protoc` using `--descriptor_set_out`.
+    /// Call it (or [`GatewayBuilder::with_descriptor_set_file`]) once per descriptor set to
+    /// merge several sets into one schema.
     pub fn with_descriptor_set_bytes(mut self, bytes: impl AsRef<[u8]>) -> Self {
         self.schema_builder = self.schema_builder.with_descriptor_set_bytes(bytes);
         self
@@ -162,12 +172,61 @@ impl GatewayBuilder {
         self
     }
 
+    /// Choose how protobuf map fields are exposed in the schema.
+    pub fn with_map_representation(mut self, representation: MapRepresentation) -> Self {
+        self.schema_builder = self.schema_builder.with_map_representation(representation);
+        self
+    }
+
+    /// Copy proto comments into GraphQL descriptions (enabled by default).
+    pub fn with_descriptions(mut self, enabled: bool) -> Self {
+        self.schema_builder = self.schema_builder.with_descriptions(enabled);
+        self
+    }
+
+    /// Choose how GraphQL names are derived from protobuf names.
+    pub fn with_naming_strategy(mut self, naming: impl NamingStrategy + 'static) -> Self {
+        self.schema_builder = self.schema_builder.with_naming_strategy(naming);
+        self
+    }
+
+    /// Suffix appended to input object names (defaults to `Input`).
+    pub fn with_input_type_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.schema_builder = self.schema_builder.with_input_type_suffix(suffix);
+        self
+    }
+
+    /// Expose methods without `graphql.schema` options by naming convention (disabled by
+    /// default); see [`SchemaBuilder::with_auto_expose`].
+    pub fn with_auto_expose(mut self, enabled: bool) -> Self {
+        self.schema_builder = self.schema_builder.with_auto_expose(enabled);
+        self
+    }
+
+    /// Wait time for further keys before a batched nested resolver RPC is sent; see
+    /// [`SchemaBuilder::with_batch_delay`].
+    pub fn with_batch_delay(mut self, delay: Duration) -> Self {
+        self.schema_builder = self.schema_builder.with_batch_delay(delay);
+        self
+    }
+
     /// Provide a protobuf descriptor set file
     pub fn with_descriptor_set_file(mut self, path: impl AsRef<Path>) -> Result<Self> {
         self.schema_builder = self.schema_builder.with_descriptor_set_file(path)?;
         Ok(self)
     }
 
+    /// Fetch descriptors from the registered gRPC clients through server reflection.
+    ///
+    /// Add the clients first; see [`SchemaBuilder::with_server_reflection`].
+    pub async fn with_server_reflection(mut self) -> Result<Self> {
+        self.schema_builder = self
+            .schema_builder
+            .with_server_reflection(&self.client_pool)
+            .await?;
+        Ok(self)
+    }
+
     /// Provide a handler to inspect/augment GraphQL errors before they are returned.
     pub fn with_error_handler<F>(mut self, handler: F) -> Self
     where
@@ -187,8 +246,8 @@ impl GatewayBuilder {
             schema_builder = schema_builder.with_services(services);
         }
 
-        let schema = schema_builder.build(&self.client_pool)?;
-        let mut mux = ServeMux::new(schema.clone());
+        let schema = SchemaHandle::build(schema_builder, self.client_pool.clone())?;
+        let mut mux = ServeMux::with_schema_handle(schema.clone());
 
         // Add middlewares
         for middleware in self.middlewares {
@@ -110,6 +110,11 @@ pub struct GraphqlSchema {
     /// Query response object configuration
     #[prost(message, optional, tag = "4")]
     pub response: ::core::option::Option<GraphqlResponse>,
+    /// Mark this operation as @deprecated with the given reason.
+    /// Methods with `option deprecated = true` are deprecated as well; their reason falls back
+    /// to the method's trailing comment.
+    #[prost(string, tag = "5")]
+    pub deprecation_reason: ::prost::alloc::string::String,
 }
 /// configuration option for request
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -122,6 +127,16 @@ pub struct GraphqlRequest {
     /// Define pluck message fields
     #[prost(string, repeated, tag = "2")]
     pub plucks: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Fill the request's `read_mask` (or `field_mask`) google.protobuf.FieldMask field from
+    /// the GraphQL selection set, so the backend can skip fields the client did not select.
+    /// The mask field is no longer exposed as an argument.
+    #[prost(bool, tag = "3")]
+    pub field_mask: bool,
+    /// Fill the request's `update_mask` google.protobuf.FieldMask with the input fields the
+    /// client sent, including explicit nulls, for patch-style updates. Paths are relative to
+    /// the resource when the request has a single message field besides the mask.
+    #[prost(bool, tag = "4")]
+    pub update_mask: bool,
 }
 /// configuration option for response
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -135,6 +150,19 @@ pub struct GraphqlResponse {
     /// It means the response could only be single.
     #[prost(string, tag = "2")]
     pub pluck: ::prost::alloc::string::String,
+    /// Expose an AIP-style paginated list as a Relay connection (`XConnection` with
+    /// `edges { node cursor }` and `pageInfo`). The request needs `int32 page_size` and
+    /// `string page_token`, the response `string next_page_token` and a repeated message
+    /// field (chosen with "pluck" when there are several). `first`/`after` arguments
+    /// replace the page fields.
+    #[prost(bool, tag = "3")]
+    pub connection: bool,
+    /// Batch the nested loads of a RESOLVER method. Names the key field of the resolved
+    /// items (e.g. "id"), which is also read from each parent. The request must consist of a
+    /// single repeated field of the key's type, which receives the collected keys; the items
+    /// are the "pluck" field, or the only repeated response field of the resolved type.
+    #[prost(string, tag = "4")]
+    pub batch_key: ::prost::alloc::string::String,
 }
 /// GraphqlField is FieldOptions in protobuf in order to define type field attribute.
 /// User can use this option as following:
@@ -179,6 +207,11 @@ pub struct GraphqlField {
     /// Federation: Mark this field as shareable (can be resolved from multiple subgraphs)
     #[prost(bool, tag = "9")]
     pub shareable: bool,
+    /// Mark this field as @deprecated with the given reason.
+    /// Fields with `\[deprecated = true\]` are deprecated as well; their reason falls back
+    /// to the field's trailing comment.
+    #[prost(string, tag = "10")]
+    pub deprecation_reason: ::prost::alloc::string::String,
 }
 /// Federation configuration for message types (entities).
 /// User can use this option to define federated entities:
@@ -1,11 +1,14 @@
 // This file is @generated by prost-build.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HelloRequest {
+    /// Who to greet.
     #[prost(string, tag = "1")]
     pub name: ::prost::alloc::string::String,
 }
+/// A rendered greeting.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HelloReply {
+    /// The greeting text, e.g. "Hello, World!".
     #[prost(string, tag = "1")]
     pub message: ::prost::alloc::string::String,
     #[prost(message, optional, tag = "2")]
@@ -78,6 +81,7 @@ pub mod greeter_client {
     )]
     use tonic::codegen::*;
     use tonic::codegen::http::Uri;
+    /// Greets people and keeps track of the current salutation.
     #[derive(Debug, Clone)]
     pub struct GreeterClient<T> {
         inner: tonic::client::Grpc<T>,
@@ -158,6 +162,7 @@ pub mod greeter_client {
             self.inner = self.inner.max_encoding_message_size(limit);
             self
         }
+        /// Greet a single person.
         pub async fn say_hello(
             &mut self,
             request: impl tonic::IntoRequest<super::HelloRequest>,
@@ -242,6 +247,27 @@ pub mod greeter_client {
                 .insert(GrpcMethod::new("greeter.Greeter", "ResolveUser"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn update_user(
+            &mut self,
+            request: impl tonic::IntoRequest<super::User>,
+        ) -> std::result::Result<tonic::Response<super::User>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/greeter.Greeter/UpdateUser",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("greeter.Greeter", "UpdateUser"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn upload_avatar(
             &mut self,
             request: impl tonic::IntoRequest<super::UploadAvatarRequest>,
@@ -305,6 +331,7 @@ pub mod greeter_server {
     /// Generated trait containing gRPC methods that should be implemented for use with GreeterServer.
     #[async_trait]
     pub trait Greeter: std::marker::Send + std::marker::Sync + 'static {
+        /// Greet a single person.
         async fn say_hello(
             &self,
             request: tonic::Request<super::HelloRequest>,
@@ -330,6 +357,10 @@ pub mod greeter_server {
             &self,
             request: tonic::Request<super::GetUserRequest>,
         ) -> std::result::Result<tonic::Response<super::User>, tonic::Status>;
+        async fn update_user(
+            &self,
+            request: tonic::Request<super::User>,
+        ) -> std::result::Result<tonic::Response<super::User>, tonic::Status>;
         async fn upload_avatar(
             &self,
             request: tonic::Request<super::UploadAvatarRequest>,
@@ -345,6 +376,7 @@ pub mod greeter_server {
             tonic::Status,
         >;
     }
+    /// Greets people and keeps track of the current salutation.
     #[derive(Debug)]
     pub struct GreeterServer<T> {
         inner: Arc<T>,
@@ -598,6 +630,49 @@ pub mod greeter_server {
                     };
                     Box::pin(fut)
                 }
+                "/greeter.Greeter/UpdateUser" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateUserSvc<T: Greeter>(pub Arc<T>);
+                    impl<T: Greeter> tonic::server::UnaryService<super::User>
+                    for UpdateUserSvc<T> {
+                        type Response = super::User;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::User>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Greeter>::update_user(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateUserSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/greeter.Greeter/UploadAvatar" => {
                     #[allow(non_camel_case_types)]
                     struct UploadAvatarSvc<T: Greeter>(pub Arc<T>);
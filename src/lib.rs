@@ -57,8 +57,12 @@ pub mod federation;
 pub mod gateway;
 pub mod grpc_client;
 pub mod middleware;
+pub mod naming;
+mod reflection;
+pub mod reload;
 pub mod runtime;
 pub mod schema;
+pub mod schema_diff;
 pub mod types;
 
 pub use dataloader::EntityDataLoader;
@@ -68,7 +72,10 @@ pub use federation::{
     GrpcEntityResolverBuilder,
 };
 pub use gateway::{Gateway, GatewayBuilder};
-pub use grpc_client::GrpcClient;
+pub use grpc_client::{GrpcClient, GrpcClientPool};
 pub use middleware::{Context, Middleware};
+pub use naming::{GraphqlNaming, NamingStrategy, ProtoNaming};
+pub use reload::SchemaHandle;
 pub use runtime::ServeMux;
-pub use schema::SchemaBuilder;
+pub use schema::{MapRepresentation, SchemaBuilder};
+pub use schema_diff::{ChangeSeverity, SchemaChange, SchemaDiff, SchemaSource};
@@ -0,0 +1,322 @@
+//! Naming strategies for generated GraphQL types, fields and enum values
+//!
+//! By default the gateway keeps protobuf names ([`ProtoNaming`]): types become
+//! `package_Message`, fields and enum values keep their proto spelling. [`GraphqlNaming`]
+//! produces conventional GraphQL names instead (`Message`, `displayName`, `ACTIVE`).
+//!
+//! Explicit `graphql.field.name` overrides always win over the strategy. Inputs are mapped
+//! back to protobuf through the same strategy, so any implementation round-trips as long
+//! as it yields unique names.
+
+use prost_reflect::{EnumValueDescriptor, FieldDescriptor, MethodDescriptor, OneofDescriptor};
+
+/// Derives GraphQL names from protobuf descriptors.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use grpc_graphql_gateway::{GraphqlNaming, SchemaBuilder};
+///
+/// let builder = SchemaBuilder::new().with_naming_strategy(GraphqlNaming::new());
+/// ```
+pub trait NamingStrategy: Send + Sync {
+    /// Name of the object, input object or enum generated for the protobuf type
+    /// `full_name` (e.g. `greeter.HelloReply`) declared in `package`.
+    fn type_name(&self, full_name: &str, package: &str) -> String;
+
+    /// Name of the GraphQL field or argument generated for a protobuf field.
+    fn field_name(&self, field: &FieldDescriptor) -> String;
+
+    /// Name of the GraphQL enum item generated for a protobuf enum value.
+    fn enum_value_name(&self, value: &EnumValueDescriptor) -> String;
+
+    /// Name of the discriminator field reporting which member of a oneof is set.
+    fn oneof_case_field_name(&self, oneof: &OneofDescriptor) -> String {
+        format!("{}_case", oneof.name())
+    }
+
+    /// Name of the root field generated for an RPC without a `graphql.schema` option,
+    /// when unannotated methods are exposed (`GetUser` → `getUser`).
+    fn operation_name(&self, method: &MethodDescriptor) -> String {
+        let mut chars = method.name().chars();
+        match chars.next() {
+            Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+            None => String::new(),
+        }
+    }
+}
+
+/// Protobuf names as-is: `package_Message` types, snake_case fields, full enum values.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProtoNaming;
+
+impl NamingStrategy for ProtoNaming {
+    fn type_name(&self, full_name: &str, _package: &str) -> String {
+        full_name.replace('.', "_")
+    }
+
+    fn field_name(&self, field: &FieldDescriptor) -> String {
+        field.name().to_string()
+    }
+
+    fn enum_value_name(&self, value: &EnumValueDescriptor) -> String {
+        value.name().to_string()
+    }
+}
+
+/// Conventional GraphQL names; each conversion can be toggled individually.
+///
+/// [`GraphqlNaming::new`] enables all of them:
+/// - strip the package from type names (`greeter.HelloReply` → `HelloReply`)
+/// - PascalCase type names, including nested types (`Outer.Inner` → `OuterInner`)
+/// - camelCase field names, honoring `json_name` (`display_name` → `displayName`)
+/// - strip enum value prefixes (`Status.STATUS_ACTIVE` → `ACTIVE`)
+#[derive(Clone, Copy, Debug)]
+pub struct GraphqlNaming {
+    strip_package: bool,
+    pascal_case_types: bool,
+    camel_case_fields: bool,
+    strip_enum_prefixes: bool,
+}
+
+impl GraphqlNaming {
+    /// Enable every conversion.
+    pub fn new() -> Self {
+        Self {
+            strip_package: true,
+            pascal_case_types: true,
+            camel_case_fields: true,
+            strip_enum_prefixes: true,
+        }
+    }
+
+    /// Drop the protobuf package from type names.
+    ///
+    /// Messages with the same name in different packages then collide, which fails the
+    /// schema build.
+    pub fn strip_package(mut self, enabled: bool) -> Self {
+        self.strip_package = enabled;
+        self
+    }
+
+    /// PascalCase type names instead of joining name segments with `_`.
+    pub fn pascal_case_types(mut self, enabled: bool) -> Self {
+        self.pascal_case_types = enabled;
+        self
+    }
+
+    /// Use the field's `json_name` (camelCase unless overridden in the proto).
+    pub fn camel_case_fields(mut self, enabled: bool) -> Self {
+        self.camel_case_fields = enabled;
+        self
+    }
+
+    /// Strip the `ENUM_NAME_` prefix from enum values when every value of the enum has it.
+    pub fn strip_enum_prefixes(mut self, enabled: bool) -> Self {
+        self.strip_enum_prefixes = enabled;
+        self
+    }
+}
+
+impl Default for GraphqlNaming {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NamingStrategy for GraphqlNaming {
+    fn type_name(&self, full_name: &str, package: &str) -> String {
+        let name = if self.strip_package && !package.is_empty() {
+            full_name
+                .strip_prefix(package)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .unwrap_or(full_name)
+        } else {
+            full_name
+        };
+
+        if self.pascal_case_types {
+            name.split(['.', '_'])
+                .filter(|segment| !segment.is_empty())
+                .map(capitalize)
+                .collect()
+        } else {
+            name.replace('.', "_")
+        }
+    }
+
+    fn field_name(&self, field: &FieldDescriptor) -> String {
+        if self.camel_case_fields {
+            field.json_name().to_string()
+        } else {
+            field.name().to_string()
+        }
+    }
+
+    fn enum_value_name(&self, value: &EnumValueDescriptor) -> String {
+        if !self.strip_enum_prefixes {
+            return value.name().to_string();
+        }
+
+        let prefix = format!("{}_", screaming_snake_case(value.parent_enum().name()));
+        let strippable = |name: &str| {
+            name.strip_prefix(&prefix)
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_'))
+        };
+        // Strip all values or none, so the enum stays consistent and collision free.
+        if value.parent_enum().values().all(|v| strippable(v.name())) {
+            value.name()[prefix.len()..].to_string()
+        } else {
+            value.name().to_string()
+        }
+    }
+
+    fn oneof_case_field_name(&self, oneof: &OneofDescriptor) -> String {
+        let name = format!("{}_case", oneof.name());
+        if self.camel_case_fields {
+            camel_case(&name)
+        } else {
+            name
+        }
+    }
+}
+
+fn capitalize(segment: &str) -> String {
+    let mut chars = segment.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = !out.is_empty();
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `StatusCode` → `STATUS_CODE`, `HTTPMethod` → `HTTP_METHOD`.
+fn screaming_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_lower)
+            {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_uppercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_names() {
+        let naming = GraphqlNaming::new();
+        assert_eq!(
+            naming.type_name("greeter.HelloReply", "greeter"),
+            "HelloReply"
+        );
+        assert_eq!(
+            naming.type_name("greeter.LabeledHello.LabelsEntry", "greeter"),
+            "LabeledHelloLabelsEntry"
+        );
+        assert_eq!(
+            naming
+                .strip_package(false)
+                .type_name("my.pkg.User", "my.pkg"),
+            "MyPkgUser"
+        );
+        assert_eq!(
+            naming
+                .pascal_case_types(false)
+                .type_name("greeter.GreetReply.style_case", "greeter"),
+            "GreetReply_style_case"
+        );
+
+        assert_eq!(camel_case("style_case"), "styleCase");
+        assert_eq!(screaming_snake_case("Tone"), "TONE");
+        assert_eq!(screaming_snake_case("StatusCode"), "STATUS_CODE");
+        assert_eq!(screaming_snake_case("HTTPMethod"), "HTTP_METHOD");
+    }
+
+    /// The GraphQL names of the values of a `naming.<name>` enum.
+    fn enum_value_names(naming: &GraphqlNaming, name: &str, values: &[&str]) -> Vec<String> {
+        let file = prost_types::FileDescriptorProto {
+            name: Some(format!("{name}.proto")),
+            package: Some("naming".into()),
+            syntax: Some("proto3".into()),
+            enum_type: vec![prost_types::EnumDescriptorProto {
+                name: Some(name.into()),
+                value: values
+                    .iter()
+                    .zip(0..)
+                    .map(|(value, number)| prost_types::EnumValueDescriptorProto {
+                        name: Some(value.to_string()),
+                        number: Some(number),
+                        options: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut pool = prost_reflect::DescriptorPool::new();
+        pool.add_file_descriptor_proto(file).expect("valid enum");
+        pool.get_enum_by_name(&format!("naming.{name}"))
+            .expect("enum")
+            .values()
+            .map(|value| naming.enum_value_name(&value))
+            .collect()
+    }
+
+    #[test]
+    fn strips_enum_value_prefixes_all_or_nothing() {
+        let naming = GraphqlNaming::new();
+        assert_eq!(
+            enum_value_names(
+                &naming,
+                "StatusCode",
+                &["STATUS_CODE_UNSPECIFIED", "STATUS_CODE_OK"]
+            ),
+            ["UNSPECIFIED", "OK"]
+        );
+        // One value without the prefix keeps every value as is.
+        assert_eq!(
+            enum_value_names(&naming, "Color", &["COLOR_UNSPECIFIED", "RED"]),
+            ["COLOR_UNSPECIFIED", "RED"]
+        );
+        // `LEVEL_1` would become `1`, which is not a valid GraphQL name.
+        assert_eq!(
+            enum_value_names(&naming, "Level", &["LEVEL_UNSPECIFIED", "LEVEL_1"]),
+            ["LEVEL_UNSPECIFIED", "LEVEL_1"]
+        );
+        assert_eq!(
+            enum_value_names(
+                &naming.strip_enum_prefixes(false),
+                "Tone",
+                &["TONE_UNSPECIFIED", "TONE_WARM"]
+            ),
+            ["TONE_UNSPECIFIED", "TONE_WARM"]
+        );
+    }
+}
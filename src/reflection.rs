@@ -0,0 +1,237 @@
+//! Descriptor discovery through gRPC server reflection (`grpc.reflection.v1`, falling back
+//! to `grpc.reflection.v1alpha`)
+//!
+//! Used by [`SchemaBuilder::with_server_reflection`](crate::SchemaBuilder::with_server_reflection)
+//! to build the schema from the descriptors the backends serve themselves.
+
+use crate::error::{Error, Result};
+use crate::grpc_client::GrpcClient;
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, ExtensionDescriptor};
+use std::collections::BTreeMap;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::v1::{ServerReflectionRequest, ServerReflectionResponse};
+
+/// Package of the reflection services themselves, which have no GraphQL mapping.
+const REFLECTION_PACKAGE_PREFIX: &str = "grpc.reflection.";
+
+/// `ServerReflectionInfo` of `v1` and of `v1alpha`, which some servers still only implement.
+/// Both versions share the same messages, so the `v1` types are used for either.
+const REFLECTION_PATHS: [&str; 2] = [
+    "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo",
+    "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo",
+];
+
+/// Extensions of `graphql.proto` that mark what the gateway exposes.
+const GRAPHQL_EXTENSIONS: [&str; 3] = ["graphql.service", "graphql.schema", "graphql.field"];
+
+/// `FileDescriptorSet` holding already encoded files, so custom options survive untouched.
+#[derive(Clone, PartialEq, Message)]
+struct EncodedFileDescriptorSet {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    file: Vec<Vec<u8>>,
+}
+
+/// Fetch the files declaring the services of `client`'s server, with all transitive
+/// dependencies, as an encoded `FileDescriptorSet`.
+pub(crate) async fn fetch_descriptor_set(client: &GrpcClient) -> Result<Vec<u8>> {
+    let mut session =
+        ReflectionSession::open(client, MessageRequest::ListServices(String::new())).await?;
+    let services: Vec<String> = match session.next().await? {
+        MessageResponse::ListServicesResponse(list) => list
+            .service
+            .into_iter()
+            .map(|service| service.name)
+            .filter(|name| !name.starts_with(REFLECTION_PACKAGE_PREFIX))
+            .collect(),
+        _ => return Err(session.error("unexpected response to list_services")),
+    };
+
+    // Files keyed by name; the server may send a file again along with other requests.
+    let mut files = BTreeMap::new();
+    let mut missing = Vec::new();
+    for service in services {
+        let response = session
+            .request(MessageRequest::FileContainingSymbol(service))
+            .await?;
+        session.collect_files(response, &mut files, &mut missing)?;
+    }
+    while let Some(name) = missing.pop() {
+        if files.contains_key(&name) {
+            continue;
+        }
+        let response = session
+            .request(MessageRequest::FileByFilename(name))
+            .await?;
+        session.collect_files(response, &mut files, &mut missing)?;
+    }
+
+    let set = EncodedFileDescriptorSet {
+        file: files.into_values().collect(),
+    }
+    .encode_to_vec();
+    check_graphql_annotations(&set).map_err(|reason| session.error(reason))?;
+    Ok(set)
+}
+
+/// Fail when the files import `graphql.proto` but carry none of its options. Servers that
+/// re-encode their descriptors through `prost_types`, like `tonic_reflection`'s
+/// `server::Builder`, drop custom options, which would otherwise surface as an empty schema.
+fn check_graphql_annotations(set: &[u8]) -> std::result::Result<(), String> {
+    let pool = DescriptorPool::decode(set).map_err(|e| format!("invalid descriptor set: {e}"))?;
+    let extensions: Vec<_> = GRAPHQL_EXTENSIONS
+        .iter()
+        .filter_map(|name| pool.get_extension_by_name(name))
+        .collect();
+    if extensions.is_empty() {
+        return Ok(());
+    }
+    let annotated = |options: DynamicMessage, extensions: &[ExtensionDescriptor]| {
+        extensions.iter().any(|ext| options.has_extension(ext))
+    };
+    let found = pool.services().any(|service| {
+        annotated(service.options(), &extensions)
+            || service
+                .methods()
+                .any(|method| annotated(method.options(), &extensions))
+    }) || pool.all_messages().any(|message| {
+        message
+            .fields()
+            .any(|field| annotated(field.options(), &extensions))
+    });
+    if found {
+        Ok(())
+    } else {
+        Err(
+            "no graphql annotations found: the served files import graphql.proto but carry \
+             none of its options, so the server likely re-encodes its descriptors (as \
+             tonic_reflection's server::Builder does); serve the descriptor set as compiled"
+                .to_string(),
+        )
+    }
+}
+
+/// One `ServerReflectionInfo` stream; requests are answered in order.
+struct ReflectionSession {
+    endpoint: String,
+    requests: mpsc::UnboundedSender<ServerReflectionRequest>,
+    responses: tonic::Streaming<ServerReflectionResponse>,
+}
+
+impl ReflectionSession {
+    /// Open the stream with `first` already queued: some servers only send response
+    /// headers once they have something to answer. Servers without `v1` are asked through
+    /// `v1alpha`.
+    async fn open(client: &GrpcClient, first: MessageRequest) -> Result<Self> {
+        let endpoint = client.endpoint().to_string();
+        let mut paths = REFLECTION_PATHS.iter().peekable();
+        while let Some(path) = paths.next() {
+            let (requests, receiver) = mpsc::unbounded_channel();
+            send(&requests, first.clone()).map_err(|reason| reflection_error(&endpoint, reason))?;
+            let mut grpc = tonic::client::Grpc::new(client.channel());
+            grpc.ready()
+                .await
+                .map_err(|e| reflection_error(&endpoint, format!("not ready: {e}")))?;
+            let response = grpc
+                .streaming(
+                    tonic::Request::new(UnboundedReceiverStream::new(receiver)),
+                    PathAndQuery::from_static(path),
+                    ProstCodec::default(),
+                )
+                .await;
+            let responses = match response {
+                Err(status)
+                    if status.code() == tonic::Code::Unimplemented && paths.peek().is_some() =>
+                {
+                    continue
+                }
+                response => response
+                    .map_err(|status| reflection_error(&endpoint, status))?
+                    .into_inner(),
+            };
+            return Ok(Self {
+                endpoint,
+                requests,
+                responses,
+            });
+        }
+        unreachable!("REFLECTION_PATHS is not empty")
+    }
+
+    async fn request(&mut self, message: MessageRequest) -> Result<MessageResponse> {
+        self.send(message)?;
+        self.next().await
+    }
+
+    fn send(&self, message: MessageRequest) -> Result<()> {
+        send(&self.requests, message).map_err(|reason| self.error(reason))
+    }
+
+    async fn next(&mut self) -> Result<MessageResponse> {
+        match self.responses.message().await {
+            Ok(Some(ServerReflectionResponse {
+                message_response: Some(MessageResponse::ErrorResponse(error)),
+                ..
+            })) => Err(self.error(format!(
+                "{} (code {})",
+                error.error_message, error.error_code
+            ))),
+            Ok(Some(ServerReflectionResponse {
+                message_response: Some(response),
+                ..
+            })) => Ok(response),
+            Ok(_) => Err(self.error("stream ended without a response")),
+            Err(status) => Err(self.error(status)),
+        }
+    }
+
+    /// Store the files of a `FileDescriptorResponse` and queue their unknown imports.
+    fn collect_files(
+        &self,
+        response: MessageResponse,
+        files: &mut BTreeMap<String, Vec<u8>>,
+        missing: &mut Vec<String>,
+    ) -> Result<()> {
+        let MessageResponse::FileDescriptorResponse(response) = response else {
+            return Err(self.error("expected a file descriptor response"));
+        };
+        for encoded in response.file_descriptor_proto {
+            // Only read for its name and imports; the encoded bytes are what gets kept.
+            let file = prost_types::FileDescriptorProto::decode(encoded.as_slice())
+                .map_err(|e| self.error(format!("invalid file descriptor: {e}")))?;
+            missing.extend(
+                file.dependency
+                    .iter()
+                    .filter(|dependency| !files.contains_key(*dependency))
+                    .cloned(),
+            );
+            files.insert(file.name().to_string(), encoded);
+        }
+        Ok(())
+    }
+
+    fn error(&self, reason: impl std::fmt::Display) -> Error {
+        reflection_error(&self.endpoint, reason)
+    }
+}
+
+fn send(
+    requests: &mpsc::UnboundedSender<ServerReflectionRequest>,
+    message: MessageRequest,
+) -> std::result::Result<(), &'static str> {
+    requests
+        .send(ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(message),
+        })
+        .map_err(|_| "stream closed")
+}
+
+fn reflection_error(endpoint: &str, reason: impl std::fmt::Display) -> Error {
+    Error::Schema(format!("server reflection on {endpoint} failed: {reason}"))
+}
@@ -0,0 +1,289 @@
+//! Hot reloading of the GraphQL schema
+//!
+//! A [`SchemaHandle`] holds the schema the gateway currently serves. Reloading rebuilds it
+//! from the original [`SchemaBuilder`], with descriptor files read again and reflection
+//! endpoints queried again, and swaps it in for new requests. Requests and subscriptions
+//! that already started keep the schema they started with. A failed rebuild is logged and
+//! leaves the current schema in place.
+
+use crate::error::{Error, Result};
+use crate::grpc_client::GrpcClientPool;
+use crate::schema::{DynamicSchema, SchemaBuilder};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+
+/// Shared handle to the current schema; clones refer to the same schema.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use grpc_graphql_gateway::Gateway;
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let gateway = Gateway::builder()
+///     .with_descriptor_set_file("descriptor.bin")?
+///     .build()?;
+///
+/// let schema = gateway.schema_handle();
+/// schema.watch_descriptor_files(Duration::from_secs(2))?;
+/// #[cfg(unix)]
+/// schema.reload_on_sighup()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SchemaHandle {
+    current: Arc<RwLock<DynamicSchema>>,
+    source: Option<Arc<ReloadSource>>,
+}
+
+/// What a reloadable schema is rebuilt from.
+struct ReloadSource {
+    /// Locked for the whole rebuild, so concurrent reloads apply in order.
+    builder: tokio::sync::Mutex<SchemaBuilder>,
+    client_pool: GrpcClientPool,
+    files: Vec<PathBuf>,
+}
+
+impl SchemaHandle {
+    /// Wrap a fixed schema; [`SchemaHandle::reload`] fails on such a handle.
+    pub fn new(schema: DynamicSchema) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(schema)),
+            source: None,
+        }
+    }
+
+    /// Build the schema and keep the builder to rebuild it on reload.
+    pub fn build(builder: SchemaBuilder, client_pool: GrpcClientPool) -> Result<Self> {
+        let schema = builder.clone().build(&client_pool)?;
+        Ok(Self {
+            current: Arc::new(RwLock::new(schema)),
+            source: Some(Arc::new(ReloadSource {
+                files: builder.descriptor_files(),
+                builder: tokio::sync::Mutex::new(builder),
+                client_pool,
+            })),
+        })
+    }
+
+    /// The schema to use for a new request.
+    pub fn current(&self) -> DynamicSchema {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Rebuild the schema from its descriptor sources and swap it in.
+    ///
+    /// On failure the error is logged and returned, and the current schema stays in place.
+    pub async fn reload(&self) -> Result<()> {
+        let Some(source) = &self.source else {
+            return Err(Error::Schema(
+                "schema was not built by the gateway and cannot be reloaded".into(),
+            ));
+        };
+
+        let builder = source.builder.lock().await;
+        let rebuilt = async {
+            let mut builder = builder.clone();
+            builder.refresh_descriptor_sets().await?;
+            builder.build(&source.client_pool)
+        }
+        .await;
+
+        match rebuilt {
+            Ok(schema) => {
+                *self.current.write().unwrap() = schema;
+                tracing::info!("GraphQL schema reloaded");
+                Ok(())
+            }
+            Err(err) => {
+                tracing::error!("GraphQL schema reload failed, keeping the current schema: {err}");
+                Err(err)
+            }
+        }
+    }
+
+    /// Reload whenever one of the descriptor set files changes, checking every `interval`.
+    ///
+    /// Only sets added with `with_descriptor_set_file` are watched; without any, nothing is
+    /// spawned and an error is returned. A file caught halfway through a write fails the
+    /// rebuild; the next change triggers another attempt.
+    pub fn watch_descriptor_files(&self, interval: Duration) -> Result<JoinHandle<()>> {
+        let handle = self.clone();
+        let files = self
+            .source
+            .as_ref()
+            .map(|source| source.files.clone())
+            .unwrap_or_default();
+        if files.is_empty() {
+            return Err(Error::Schema(
+                "schema has no descriptor set files to watch".into(),
+            ));
+        }
+
+        // Taken before spawning, so changes made before the task first runs are not missed.
+        let mut seen = modified_times(&files);
+        Ok(tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                let modified = modified_times(&files);
+                if modified != seen {
+                    seen = modified;
+                    // Failures are logged by `reload`.
+                    let _ = handle.reload().await;
+                }
+            }
+        }))
+    }
+
+    /// Reload whenever the process receives `SIGHUP`.
+    #[cfg(unix)]
+    pub fn reload_on_sighup(&self) -> Result<JoinHandle<()>> {
+        let mut hangups = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        let handle = self.clone();
+
+        Ok(tokio::spawn(async move {
+            while hangups.recv().await.is_some() {
+                let _ = handle.reload().await;
+            }
+        }))
+    }
+}
+
+fn modified_times(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| {
+            std::fs::metadata(file)
+                .and_then(|meta| meta.modified())
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::tests::type_fields;
+
+    const GREETER_DESCRIPTOR: &[u8] = include_bytes!("generated/greeter_descriptor.bin");
+    const RECURSIVE_DESCRIPTOR: &[u8] = include_bytes!("generated/recursive_descriptor.bin");
+
+    /// A descriptor set file named after the test, so tests running in parallel (and
+    /// other test processes) never share one.
+    fn descriptor_file(test: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "grpc-graphql-gateway-{test}-{}.bin",
+            std::process::id()
+        ));
+        std::fs::write(&path, bytes).expect("write descriptor");
+        path
+    }
+
+    /// Rewrite `path` with a modification time that differs from the previous one even on
+    /// file systems with coarse timestamps.
+    fn rewrite(path: &PathBuf, bytes: &[u8]) {
+        let previous = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .expect("modification time");
+        std::fs::write(path, bytes).expect("write descriptor");
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(previous + Duration::from_secs(1)))
+            .expect("set modification time");
+    }
+
+    fn reloadable(path: &PathBuf) -> SchemaHandle {
+        SchemaHandle::build(
+            SchemaBuilder::new()
+                .with_descriptor_set_file(path)
+                .expect("read descriptor"),
+            GrpcClientPool::new(),
+        )
+        .expect("schema builds")
+    }
+
+    /// Wait until the served schema has a `thread` query, failing after a few seconds.
+    async fn wait_for_thread_query(handle: &SchemaHandle) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !type_fields(&handle.current(), "Query")
+                .await
+                .contains_key("thread")
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("schema reloaded");
+    }
+
+    #[tokio::test]
+    async fn reload_swaps_schema_and_keeps_it_on_failure() {
+        let path = descriptor_file("reload", GREETER_DESCRIPTOR);
+        let handle = reloadable(&path);
+        let before = handle.current();
+        assert!(type_fields(&before, "Query").await.contains_key("hello"));
+
+        std::fs::write(&path, RECURSIVE_DESCRIPTOR).expect("write descriptor");
+        handle.reload().await.expect("reload succeeds");
+        let reloaded = handle.current();
+        let query = type_fields(&reloaded, "Query").await;
+        assert!(
+            query.contains_key("thread") && !query.contains_key("hello"),
+            "{query:?}"
+        );
+        // Requests that already hold the old schema keep using it.
+        assert!(type_fields(&before, "Query").await.contains_key("hello"));
+
+        std::fs::write(&path, b"not a descriptor set").expect("write descriptor");
+        assert!(handle.reload().await.is_err());
+        assert_eq!(handle.current().sdl(), reloaded.sdl());
+
+        std::fs::remove_file(&path).ok();
+        assert!(SchemaHandle::new(before).reload().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn watcher_reloads_changed_descriptor_files() {
+        let path = descriptor_file("watch", GREETER_DESCRIPTOR);
+        let handle = reloadable(&path);
+        let watcher = handle
+            .watch_descriptor_files(Duration::from_millis(20))
+            .expect("files to watch");
+
+        rewrite(&path, RECURSIVE_DESCRIPTOR);
+        wait_for_thread_query(&handle).await;
+        watcher.abort();
+        std::fs::remove_file(&path).ok();
+
+        // Without descriptor files there is nothing to watch.
+        let fixed = SchemaHandle::new(handle.current());
+        assert!(fixed
+            .watch_descriptor_files(Duration::from_millis(20))
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn sighup_reloads_the_schema() {
+        let path = descriptor_file("sighup", GREETER_DESCRIPTOR);
+        let handle = reloadable(&path);
+        let listener = handle.reload_on_sighup().expect("SIGHUP handler installed");
+
+        std::fs::write(&path, RECURSIVE_DESCRIPTOR).expect("write descriptor");
+        let status = std::process::Command::new("kill")
+            .args(["-HUP", &std::process::id().to_string()])
+            .status()
+            .expect("run kill");
+        assert!(status.success());
+        wait_for_thread_query(&handle).await;
+        listener.abort();
+        std::fs::remove_file(&path).ok();
+    }
+}
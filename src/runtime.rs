@@ -2,24 +2,26 @@
 
 use crate::error::{GraphQLError, Result};
 use crate::middleware::{Context, Middleware};
+use crate::reload::SchemaHandle;
 use crate::schema::{DynamicSchema, GrpcResponseCache};
 use async_graphql::ServerError;
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use axum::{
-    extract::State,
+    extract::{Request, State},
     http::HeaderMap,
-    response::{Html, IntoResponse},
-    routing::{get_service, post},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
     Extension, Router,
 };
 use std::sync::Arc;
+use tower::ServiceExt;
 
 /// ServeMux - main gateway handler
 ///
 /// The `ServeMux` handles the routing of GraphQL requests, executing middlewares,
 /// and invoking the dynamic schema. It can be converted into an Axum router.
 pub struct ServeMux {
-    schema: DynamicSchema,
+    schema: SchemaHandle,
     middlewares: Vec<Arc<dyn Middleware>>,
     error_handler: Option<Arc<dyn Fn(Vec<GraphQLError>) + Send + Sync>>,
 }
@@ -27,6 +29,11 @@ pub struct ServeMux {
 impl ServeMux {
     /// Create a new ServeMux with an already built schema
     pub fn new(schema: DynamicSchema) -> Self {
+        Self::with_schema_handle(SchemaHandle::new(schema))
+    }
+
+    /// Create a new ServeMux serving whatever schema `schema` currently holds
+    pub fn with_schema_handle(schema: SchemaHandle) -> Self {
         Self {
             schema,
             middlewares: Vec::new(),
@@ -78,7 +85,7 @@ impl ServeMux {
         gql_request = gql_request.data(ctx);
         gql_request = gql_request.data(GrpcResponseCache::default());
 
-        Ok(self.schema.execute(gql_request).await)
+        Ok(self.schema.current().execute(gql_request).await)
     }
 
     /// Handle GraphQL HTTP request
@@ -109,15 +116,15 @@ impl ServeMux {
     /// Convert to Axum router
     pub fn into_router(self) -> Router {
         let state = Arc::new(self);
-        let subscription = GraphQLSubscription::new(state.schema.executor());
+        let schema = state.schema.clone();
 
         Router::new()
             .route(
                 "/graphql",
                 post(handle_graphql_post).get(graphql_playground),
             )
-            .route_service("/graphql/ws", get_service(subscription))
-            .layer(Extension(state.schema.executor()))
+            .route("/graphql/ws", get(handle_graphql_ws))
+            .layer(Extension(schema))
             .with_state(state)
     }
 }
@@ -141,6 +148,17 @@ async fn handle_graphql_post(
     mux.handle_http(headers, request).await
 }
 
+/// Handler for WebSocket subscriptions on /graphql/ws
+///
+/// Each connection is served by the schema that is current when it opens, so a reload
+/// leaves running subscriptions untouched.
+async fn handle_graphql_ws(State(mux): State<Arc<ServeMux>>, request: Request) -> Response {
+    GraphQLSubscription::new(mux.schema.current().executor())
+        .oneshot(request)
+        .await
+        .unwrap_or_else(|never| match never {})
+}
+
 /// Serve the GraphQL Playground UI for ad-hoc exploration.
 async fn graphql_playground() -> impl IntoResponse {
     Html(async_graphql::http::playground_source(
@@ -8,31 +8,70 @@ use crate::error::{Error, Result};
 use crate::federation::{EntityResolver, FederationConfig, GrpcEntityResolver};
 use crate::graphql::{GraphqlField, GraphqlResponse, GraphqlSchema, GraphqlService, GraphqlType};
 use crate::grpc_client::{GrpcClient, GrpcClientPool};
+use crate::naming::{NamingStrategy, ProtoNaming};
+use async_graphql::dataloader::{DataLoader, Loader};
 use async_graphql::dynamic::{
     Enum, EnumItem, Field, FieldFuture, FieldValue, InputObject, InputValue, Object,
-    ResolverContext, Schema as AsyncSchema, Subscription, SubscriptionField,
+    ResolverContext, Scalar, Schema as AsyncSchema, Subscription, SubscriptionField,
     SubscriptionFieldFuture, TypeRef,
 };
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest,
+};
 use async_graphql::futures_util::StreamExt;
 use async_graphql::indexmap::IndexMap;
-use async_graphql::{Name, UploadValue, Value as GqlValue};
+use async_graphql::{
+    Name, SDLExportOptions, SelectionField, ServerResult, UploadValue, Value as GqlValue,
+};
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use prost::bytes::Buf;
 use prost::Message;
 use prost_reflect::{
-    DescriptorPool, DynamicMessage, EnumDescriptor, ExtensionDescriptor, FieldDescriptor, Kind,
-    MapKey, MessageDescriptor, ReflectMessage, Value,
+    DescriptorPool, DynamicMessage, EnumDescriptor, EnumValueDescriptor, ExtensionDescriptor,
+    FieldDescriptor, FileDescriptor, Kind, MapKey, MessageDescriptor, MethodDescriptor,
+    OneofDescriptor, ReflectMessage, ServiceDescriptor, Value,
 };
-use std::collections::{HashMap, HashSet};
+use prost_types::source_code_info::Location;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tonic::client::Grpc;
 use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
 use tonic::codegen::http;
 use tonic::Status;
 
+/// Name of the scalar used for free-form JSON values (e.g. maps in [`MapRepresentation::Json`]).
+const JSON_SCALAR: &str = "JSON";
+/// Name of the RFC 3339 scalar used for `google.protobuf.Timestamp`.
+const DATETIME_SCALAR: &str = "DateTime";
+/// Signed 64-bit integers, serialized as strings so they survive JSON number precision.
+const INT64_SCALAR: &str = "Int64";
+/// Unsigned 64-bit integers, serialized as strings like [`INT64_SCALAR`].
+const UINT64_SCALAR: &str = "UInt64";
+
+/// Name of the shared Relay `PageInfo` type used by connections.
+const PAGE_INFO_TYPE: &str = "PageInfo";
+
+/// Type names the gateway defines itself, which generated types must not reuse.
+const RESERVED_TYPE_NAMES: &[&str] = &[
+    "Query",
+    "Mutation",
+    "Subscription",
+    "String",
+    "Int",
+    "Float",
+    "Boolean",
+    "ID",
+    "Upload",
+    JSON_SCALAR,
+    DATETIME_SCALAR,
+    INT64_SCALAR,
+    UINT64_SCALAR,
+];
+
 /// Dynamic schema wrapper
 ///
 /// Wraps an `async-graphql` schema and provides execution capabilities.
@@ -52,6 +91,138 @@ impl DynamicSchema {
     pub fn executor(&self) -> AsyncSchema {
         self.inner.clone()
     }
+
+    /// Export the schema as SDL.
+    ///
+    /// Types, fields and enum values are sorted by name, so the output only changes when
+    /// the schema does and can be committed and diffed.
+    pub fn sdl(&self) -> String {
+        self.inner.sdl_with_options(sorted_sdl_options())
+    }
+
+    /// Export the schema as an Apollo Federation subgraph SDL (with `@link`, `@key` and
+    /// the other federation directives), sorted like [`DynamicSchema::sdl`].
+    pub fn subgraph_sdl(&self) -> String {
+        self.inner
+            .sdl_with_options(sorted_sdl_options().federation())
+    }
+}
+
+fn sorted_sdl_options() -> SDLExportOptions {
+    SDLExportOptions::new().sorted_fields().sorted_enum_items()
+}
+
+/// A descriptor set added to [`SchemaBuilder`], labeled for error messages.
+#[derive(Clone)]
+struct DescriptorSet {
+    label: String,
+    bytes: Vec<u8>,
+    /// Where to load the set again when the schema is reloaded.
+    source: DescriptorSource,
+}
+
+#[derive(Clone)]
+enum DescriptorSource {
+    Bytes,
+    File(PathBuf),
+    Reflection(GrpcClient),
+}
+
+/// Operation options for an unannotated method, derived from its name and streaming kind.
+fn conventional_operation(
+    method: &MethodDescriptor,
+    naming: &dyn NamingStrategy,
+) -> Option<GraphqlSchema> {
+    let graphql_type = if method.is_client_streaming() {
+        return None;
+    } else if method.is_server_streaming() {
+        GraphqlType::Subscription
+    } else if ["Get", "List", "Search"]
+        .iter()
+        .any(|verb| starts_with_word(method.name(), verb))
+    {
+        GraphqlType::Query
+    } else {
+        GraphqlType::Mutation
+    };
+    Some(GraphqlSchema {
+        r#type: graphql_type as i32,
+        name: naming.operation_name(method),
+        ..Default::default()
+    })
+}
+
+/// Whether `name` starts with the word `word`, so `GetUser` matches `Get` but `Getaway`
+/// does not.
+fn starts_with_word(name: &str, word: &str) -> bool {
+    name.strip_prefix(word)
+        .is_some_and(|rest| rest.chars().next().is_none_or(|c| !c.is_ascii_lowercase()))
+}
+
+/// Merge descriptor sets into one pool, loading files shared between sets once.
+fn merge_descriptor_sets(sets: &[DescriptorSet]) -> Result<DescriptorPool> {
+    let mut pool = DescriptorPool::new();
+    let mut origins: HashMap<String, &str> = HashMap::new();
+
+    for set in sets {
+        let decoded = DescriptorPool::decode(set.bytes.as_slice())
+            .map_err(|e| Error::Schema(format!("failed to decode {}: {e}", set.label)))?;
+
+        // The pool skips files it already has by name, so compare them first. The
+        // well-known types are fixed by protobuf; only their file options vary between
+        // protoc releases.
+        for file in decoded.files() {
+            if file.package_name() == "google.protobuf" {
+                continue;
+            }
+            if let Some(existing) = pool.get_file_by_name(file.name()) {
+                if file_definition(&existing)? != file_definition(&file)? {
+                    return Err(Error::Schema(format!(
+                        "{} differs between {} and {}; build both against the same version",
+                        file.name(),
+                        origins[file.name()],
+                        set.label
+                    )));
+                }
+            }
+        }
+
+        pool.decode_file_descriptor_set(set.bytes.as_slice())
+            .map_err(|e| {
+                Error::Schema(format!(
+                    "{} conflicts with previously loaded descriptor sets: {e}",
+                    set.label
+                ))
+            })?;
+        for file in decoded.files() {
+            origins.entry(file.name().to_string()).or_insert(&set.label);
+        }
+    }
+
+    Ok(pool)
+}
+
+/// A file descriptor with its custom options but without `source_code_info`, which also
+/// changes with comments and formatting.
+fn file_definition(file: &FileDescriptor) -> Result<DynamicMessage> {
+    let mut definition = DynamicMessage::decode(
+        prost_types::FileDescriptorProto::default().descriptor(),
+        file.encode_to_vec().as_slice(),
+    )
+    .map_err(|e| Error::Schema(format!("failed to decode {}: {e}", file.name())))?;
+    definition.clear_field_by_name("source_code_info");
+    Ok(definition)
+}
+
+/// How protobuf `map<K, V>` fields are exposed in GraphQL.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MapRepresentation {
+    /// A list of `{ key, value }` entry objects (one type per map, named after the
+    /// synthetic `*Entry` message). Keys and values keep their GraphQL types.
+    #[default]
+    Entries,
+    /// A single `JSON` scalar holding an object keyed by the stringified map keys.
+    Json,
 }
 
 /// Schema builder for GraphQL gateway
@@ -71,34 +242,91 @@ impl DynamicSchema {
 /// # Ok(())
 /// # }
 /// ```
+#[derive(Clone)]
 pub struct SchemaBuilder {
-    descriptor_bytes: Option<Vec<u8>>,
+    descriptor_sets: Vec<DescriptorSet>,
     federation: bool,
     entity_resolver: Option<std::sync::Arc<dyn EntityResolver>>,
     service_allowlist: Option<HashSet<String>>,
+    map_representation: MapRepresentation,
+    descriptions: bool,
+    naming: Arc<dyn NamingStrategy>,
+    input_type_suffix: String,
+    auto_expose: bool,
+    batch_delay: Duration,
 }
 
 impl SchemaBuilder {
     /// Create a new schema builder
     pub fn new() -> Self {
         Self {
-            descriptor_bytes: None,
+            descriptor_sets: Vec::new(),
             federation: false,
             entity_resolver: None,
             service_allowlist: None,
+            map_representation: MapRepresentation::default(),
+            descriptions: true,
+            naming: Arc::new(ProtoNaming),
+            input_type_suffix: "Input".to_string(),
+            auto_expose: false,
+            batch_delay: Duration::from_millis(1),
         }
     }
 
-    /// Provide a descriptor set from bytes
+    /// Add a descriptor set from bytes.
+    ///
+    /// Can be called repeatedly (also mixed with [`SchemaBuilder::with_descriptor_set_file`])
+    /// to merge descriptor sets, e.g. one per team, into a single schema. Files contained in
+    /// several sets, such as `graphql.proto`, are loaded once; the build fails if two sets
+    /// disagree on a file or define the same symbol in different files.
     pub fn with_descriptor_set_bytes(mut self, bytes: impl AsRef<[u8]>) -> Self {
-        self.descriptor_bytes = Some(bytes.as_ref().to_vec());
+        let label = format!("descriptor set #{}", self.descriptor_sets.len() + 1);
+        self.descriptor_sets.push(DescriptorSet {
+            label,
+            bytes: bytes.as_ref().to_vec(),
+            source: DescriptorSource::Bytes,
+        });
         self
     }
 
-    /// Provide a descriptor set from a file
+    /// Add a descriptor set from a file; see [`SchemaBuilder::with_descriptor_set_bytes`].
     pub fn with_descriptor_set_file(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
         let data = std::fs::read(path).map_err(Error::Io)?;
-        self.descriptor_bytes = Some(data);
+        self.descriptor_sets.push(DescriptorSet {
+            label: path.display().to_string(),
+            bytes: data,
+            source: DescriptorSource::File(path.to_path_buf()),
+        });
+        Ok(self)
+    }
+
+    /// Fetch descriptors from the backends in `client_pool` through gRPC server reflection
+    /// (`grpc.reflection.v1`, or `v1alpha` as a fallback) instead of shipping descriptor sets.
+    ///
+    /// Every distinct endpoint is asked for the files declaring its services and their
+    /// transitive imports. The result is merged like the sets added with
+    /// [`SchemaBuilder::with_descriptor_set_bytes`], so both can be combined. Servers must
+    /// return the files as compiled, with the `graphql.*` options intact; files importing
+    /// `graphql.proto` without any of its options fail with "no graphql annotations found".
+    pub async fn with_server_reflection(mut self, client_pool: &GrpcClientPool) -> Result<Self> {
+        let mut names = client_pool.names();
+        names.sort();
+        let mut endpoints = HashSet::new();
+        for name in names {
+            let Some(client) = client_pool.get(&name) else {
+                continue;
+            };
+            if !endpoints.insert(client.endpoint().to_string()) {
+                continue;
+            }
+            let bytes = crate::reflection::fetch_descriptor_set(&client).await?;
+            self.descriptor_sets.push(DescriptorSet {
+                label: format!("server reflection on {}", client.endpoint()),
+                bytes,
+                source: DescriptorSource::Reflection(client),
+            });
+        }
         Ok(self)
     }
 
@@ -126,14 +354,93 @@ impl SchemaBuilder {
         self
     }
 
+    /// Choose how protobuf map fields are exposed (defaults to [`MapRepresentation::Entries`]).
+    pub fn with_map_representation(mut self, representation: MapRepresentation) -> Self {
+        self.map_representation = representation;
+        self
+    }
+
+    /// Copy leading proto comments into GraphQL descriptions (enabled by default).
+    ///
+    /// Comments are only available when the descriptor set was generated with source
+    /// info (`protoc --include_source_info`, which `tonic-build` does by default).
+    pub fn with_descriptions(mut self, enabled: bool) -> Self {
+        self.descriptions = enabled;
+        self
+    }
+
+    /// Choose how GraphQL type, field and enum value names are derived from protobuf
+    /// (defaults to [`ProtoNaming`], which keeps the protobuf names).
+    ///
+    /// Explicit `graphql.field.name` options take precedence over the strategy.
+    pub fn with_naming_strategy(mut self, naming: impl NamingStrategy + 'static) -> Self {
+        self.naming = Arc::new(naming);
+        self
+    }
+
+    /// Suffix appended to input object names (defaults to `Input`, e.g. `UserInput`).
+    ///
+    /// Input objects need their own names because a message used in both requests and
+    /// responses becomes an input and an output type. With an empty suffix such a message
+    /// fails the build with a name collision.
+    pub fn with_input_type_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.input_type_suffix = suffix.into();
+        self
+    }
+
+    /// Expose methods without a `graphql.schema` option by naming convention (disabled by
+    /// default).
+    ///
+    /// Unary `Get*`, `List*` and `Search*` methods become queries, other unary methods
+    /// mutations and server-streaming methods subscriptions; client-streaming methods stay
+    /// unexposed. Field names come from [`NamingStrategy::operation_name`]. Annotated
+    /// methods take precedence when both end up with the same root field name.
+    pub fn with_auto_expose(mut self, enabled: bool) -> Self {
+        self.auto_expose = enabled;
+        self
+    }
+
+    /// How long nested resolver loads with a `batch_key` wait for further keys before the
+    /// batch RPC is sent (1ms by default).
+    ///
+    /// Loads are batched per request (or per subscription), whether it runs through
+    /// [`DynamicSchema::execute`] or the [`DynamicSchema::executor`].
+    pub fn with_batch_delay(mut self, delay: Duration) -> Self {
+        self.batch_delay = delay;
+        self
+    }
+
+    /// Load descriptor sets from their files and reflection endpoints again.
+    pub(crate) async fn refresh_descriptor_sets(&mut self) -> Result<()> {
+        for set in &mut self.descriptor_sets {
+            match &set.source {
+                DescriptorSource::Bytes => {}
+                DescriptorSource::File(path) => set.bytes = tokio::fs::read(path).await?,
+                DescriptorSource::Reflection(client) => {
+                    set.bytes = crate::reflection::fetch_descriptor_set(client).await?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Files the descriptor sets were loaded from.
+    pub(crate) fn descriptor_files(&self) -> Vec<PathBuf> {
+        self.descriptor_sets
+            .iter()
+            .filter_map(|set| match &set.source {
+                DescriptorSource::File(path) => Some(path.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Build the GraphQL schema from the provided descriptor set.
     pub fn build(self, client_pool: &GrpcClientPool) -> Result<DynamicSchema> {
-        let bytes = self
-            .descriptor_bytes
-            .ok_or_else(|| Error::Schema("descriptor set is required".into()))?;
-
-        let pool = DescriptorPool::decode(bytes.as_slice())
-            .map_err(|e| Error::Schema(format!("failed to decode descriptor set: {e}")))?;
+        if self.descriptor_sets.is_empty() {
+            return Err(Error::Schema("descriptor set is required".into()));
+        }
+        let pool = merge_descriptor_sets(&self.descriptor_sets)?;
 
         let method_ext = pool
             .get_extension_by_name("graphql.schema")
@@ -141,9 +448,14 @@ impl SchemaBuilder {
         let service_ext = pool
             .get_extension_by_name("graphql.service")
             .ok_or_else(|| Error::Schema("missing graphql.service extension".into()))?;
-        let field_ext = pool
-            .get_extension_by_name("graphql.field")
-            .ok_or_else(|| Error::Schema("missing graphql.field extension".into()))?;
+        let build_ctx = BuildContext {
+            field_ext: pool
+                .get_extension_by_name("graphql.field")
+                .ok_or_else(|| Error::Schema("missing graphql.field extension".into()))?,
+            behavior: pool.get_extension_by_name("google.api.field_behavior"),
+            naming: self.naming.clone(),
+            patch: false,
+        };
 
         // Load entity extension if federation is enabled
         let entity_ext = if self.federation {
@@ -154,42 +466,98 @@ impl SchemaBuilder {
 
         // Extract federation configuration
         let federation_config = if let Some(entity_ext) = entity_ext.as_ref() {
-            FederationConfig::from_descriptor_pool(&pool, entity_ext)?
+            FederationConfig::from_descriptor_pool_with_naming(
+                &pool,
+                entity_ext,
+                self.naming.as_ref(),
+            )?
         } else {
             FederationConfig::new()
         };
 
-        let mut registry = TypeRegistry::default();
+        let mut registry = TypeRegistry::new(
+            &pool,
+            client_pool.clone(),
+            self.map_representation,
+            self.descriptions,
+            self.naming.clone(),
+            self.input_type_suffix.clone(),
+        );
 
         let mut query_root: Option<Object> = None;
         let mut mutation_root: Option<Object> = None;
         let mut subscription_root: Option<Subscription> = None;
 
+        // RESOLVER methods stay off the root types; they are only reachable from nested
+        // fields through `graphql.field.resolver`, also on services outside the allowlist.
         for service in pool.services() {
-            if let Some(allowlist) = self.service_allowlist.as_ref() {
-                if !allowlist.contains(service.full_name()) {
+            for method in service.methods() {
+                let Some(schema_opts) =
+                    decode_extension::<GraphqlSchema>(&method.options(), &method_ext)?
+                else {
                     continue;
+                };
+                if GraphqlType::try_from(schema_opts.r#type) == Ok(GraphqlType::Resolver) {
+                    registry.register_resolver(&method, &schema_opts)?;
                 }
             }
+        }
 
-            let service_options =
-                decode_extension::<GraphqlService>(&service.options(), &service_ext)?
-                    .unwrap_or_default();
-
-            if !service_options.host.is_empty() && client_pool.get(service.full_name()).is_none() {
-                let client = GrpcClient::connect_lazy(
-                    service_options.host.clone(),
-                    service_options.insecure,
-                )?;
-                client_pool.add(service.full_name(), client);
+        let exposed_services: Vec<_> = pool
+            .services()
+            .filter(|service| {
+                self.service_allowlist
+                    .as_ref()
+                    .is_none_or(|allowlist| allowlist.contains(service.full_name()))
+            })
+            .collect();
+
+        // Root fields claimed by annotated methods, which win over names derived by
+        // convention.
+        let mut root_fields = HashSet::new();
+        if self.auto_expose {
+            for method in exposed_services
+                .iter()
+                .flat_map(prost_reflect::ServiceDescriptor::methods)
+            {
+                if let Some(schema_opts) =
+                    decode_extension::<GraphqlSchema>(&method.options(), &method_ext)?
+                {
+                    let graphql_type =
+                        GraphqlType::try_from(schema_opts.r#type).unwrap_or(GraphqlType::Query);
+                    root_fields.insert((graphql_type, schema_opts.name));
+                }
             }
+        }
+
+        for service in exposed_services {
+            connect_service(&service, &service_ext, client_pool)?;
 
             for method in service.methods() {
-                let Some(schema_opts) =
-                    decode_extension::<GraphqlSchema>(&method.options(), &method_ext)?
-                else {
-                    continue;
-                };
+                let schema_opts =
+                    match decode_extension::<GraphqlSchema>(&method.options(), &method_ext)? {
+                        Some(schema_opts) => schema_opts,
+                        None if self.auto_expose => {
+                            let Some(schema_opts) =
+                                conventional_operation(&method, self.naming.as_ref())
+                            else {
+                                continue;
+                            };
+                            let graphql_type = GraphqlType::try_from(schema_opts.r#type)
+                                .unwrap_or(GraphqlType::Query);
+                            if !root_fields.insert((graphql_type, schema_opts.name.clone())) {
+                                tracing::warn!(
+                                    "not exposing {}: {:?} field `{}` already exists",
+                                    method.full_name(),
+                                    graphql_type,
+                                    schema_opts.name
+                                );
+                                continue;
+                            }
+                            schema_opts
+                        }
+                        None => continue,
+                    };
 
                 let graphql_type =
                     GraphqlType::try_from(schema_opts.r#type).unwrap_or(GraphqlType::Query);
@@ -199,13 +567,14 @@ impl SchemaBuilder {
                 }
 
                 match graphql_type {
-                    GraphqlType::Query | GraphqlType::Resolver => {
+                    GraphqlType::Resolver => {}
+                    GraphqlType::Query => {
                         let field = build_field(
                             field_name,
                             &service,
                             &method,
                             &schema_opts,
-                            field_ext.clone(),
+                            build_ctx.clone(),
                             &mut registry,
                             client_pool.clone(),
                         )?;
@@ -219,7 +588,7 @@ impl SchemaBuilder {
                             &service,
                             &method,
                             &schema_opts,
-                            field_ext.clone(),
+                            build_ctx.clone(),
                             &mut registry,
                             client_pool.clone(),
                         )?;
@@ -235,7 +604,7 @@ impl SchemaBuilder {
                             &service,
                             &method,
                             &schema_opts,
-                            field_ext.clone(),
+                            build_ctx.clone(),
                             &mut registry,
                             client_pool.clone(),
                         )?;
@@ -249,6 +618,14 @@ impl SchemaBuilder {
             }
         }
 
+        // Services outside the allowlist still need a client when nested fields resolve
+        // through them.
+        for service_name in &registry.resolver_services {
+            if let Some(service) = pool.get_service_by_name(service_name) {
+                connect_service(&service, &service_ext, client_pool)?;
+            }
+        }
+
         let query_root = query_root.unwrap_or_else(placeholder_query_root);
 
         let mut schema_builder = AsyncSchema::build(
@@ -258,6 +635,13 @@ impl SchemaBuilder {
         );
 
         schema_builder = schema_builder.enable_uploading();
+        // Only schemas with batched nested resolvers need a data loader per request.
+        if registry.batched {
+            schema_builder = schema_builder.extension(BatchLoaderExtension {
+                client_pool: client_pool.clone(),
+                batch_delay: self.batch_delay,
+            });
+        }
 
         if self.federation {
             schema_builder = schema_builder.enable_federation();
@@ -336,6 +720,31 @@ impl SchemaBuilder {
             schema_builder = schema_builder.register(subscription);
         }
 
+        if registry.scalars.contains(JSON_SCALAR) {
+            schema_builder = schema_builder
+                .register(Scalar::new(JSON_SCALAR).description("Arbitrary JSON value"));
+        }
+        if registry.scalars.contains(INT64_SCALAR) {
+            schema_builder = schema_builder.register(
+                Scalar::new(INT64_SCALAR)
+                    .description("Signed 64-bit integer, serialized as a string")
+                    .validator(|value| int64_from_graphql::<i64>(value).is_some()),
+            );
+        }
+        if registry.scalars.contains(UINT64_SCALAR) {
+            schema_builder = schema_builder.register(
+                Scalar::new(UINT64_SCALAR)
+                    .description("Unsigned 64-bit integer, serialized as a string")
+                    .validator(|value| int64_from_graphql::<u64>(value).is_some()),
+            );
+        }
+        if registry.scalars.contains(DATETIME_SCALAR) {
+            schema_builder = schema_builder.register(
+                Scalar::new(DATETIME_SCALAR)
+                    .description("RFC 3339 date-time, e.g. 2024-01-01T00:00:00Z")
+                    .specified_by_url("https://datatracker.ietf.org/doc/html/rfc3339"),
+            );
+        }
         for (_, en) in registry.enums {
             schema_builder = schema_builder.register(en);
         }
@@ -367,427 +776,3587 @@ impl Default for SchemaBuilder {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use async_graphql::parser::types::{TypeKind, TypeSystemDefinition};
+    use greeter::greeter_server::{Greeter, GreeterServer};
+    use greeter::{
+        GetUserRequest, GreetMeta, HelloReply, HelloRequest, UpdateGreetingRequest,
+        UploadAvatarReply, UploadAvatarRequest, UploadAvatarsReply, UploadAvatarsRequest, User,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tonic::{Request, Response};
+
+    #[allow(clippy::all)]
+    mod greeter {
+        include!("generated/greeter.rs");
+    }
 
     const FEDERATION_DESCRIPTOR: &[u8] =
         include_bytes!("generated/federation_example_descriptor.bin");
+    const GREETER_DESCRIPTOR: &[u8] = include_bytes!("generated/greeter_descriptor.bin");
+    const RECURSIVE_DESCRIPTOR: &[u8] = include_bytes!("generated/recursive_descriptor.bin");
 
-    #[tokio::test]
-    async fn federation_adds_entities_query() {
-        let schema = SchemaBuilder::new()
-            .with_descriptor_set_bytes(FEDERATION_DESCRIPTOR)
-            .enable_federation()
-            .build(&GrpcClientPool::new())
-            .expect("schema builds");
+    #[allow(clippy::all)]
+    mod recursive {
+        include!("generated/recursive.rs");
+    }
 
-        let response = schema
-            .execute(async_graphql::Request::new(
-                "{ _entities(representations: []) { __typename } }",
-            ))
-            .await;
+    struct TestGreeter;
+
+    fn test_reply(name: &str, from: &str) -> HelloReply {
+        HelloReply {
+            message: format!("Hello, {name}!"),
+            meta: Some(GreetMeta {
+                correlation_id: format!("hello-{name}"),
+                from: Some(User {
+                    id: from.to_string(),
+                    ..Default::default()
+                }),
+            }),
+        }
+    }
 
-        assert!(
-            response.errors.is_empty(),
-            "expected _entities field to exist, got errors: {:?}",
-            response.errors
-        );
+    fn test_user(id: String) -> User {
+        User {
+            display_name: format!("User {id}"),
+            id,
+            trusted: true,
+        }
+    }
 
-        let data = response.data.into_json().expect("valid JSON response");
-        let entities = data
-            .get("_entities")
-            .and_then(|value| value.as_array())
-            .cloned()
-            .unwrap_or_default();
+    #[tonic::async_trait]
+    impl Greeter for TestGreeter {
+        async fn say_hello(
+            &self,
+            request: Request<HelloRequest>,
+        ) -> std::result::Result<Response<HelloReply>, Status> {
+            let name = request.into_inner().name;
+            Ok(Response::new(test_reply(&name, "demo")))
+        }
 
-        assert!(entities.is_empty(), "expected empty entities list");
-    }
-}
+        async fn update_greeting(
+            &self,
+            _request: Request<UpdateGreetingRequest>,
+        ) -> std::result::Result<Response<HelloReply>, Status> {
+            Err(Status::unimplemented("update_greeting"))
+        }
 
-#[derive(Default)]
-struct TypeRegistry {
-    objects: HashMap<String, Object>,
-    input_objects: HashMap<String, InputObject>,
-    enums: HashMap<String, Enum>,
-}
+        type StreamHellosStream = tokio_stream::Empty<std::result::Result<HelloReply, Status>>;
 
-/// Per-request memoization to avoid duplicate gRPC calls for identical inputs.
-#[derive(Clone, Default)]
-pub struct GrpcResponseCache {
-    inner: Arc<Mutex<HashMap<GrpcCacheKey, GqlValue>>>,
-}
+        async fn stream_hellos(
+            &self,
+            _request: Request<HelloRequest>,
+        ) -> std::result::Result<Response<Self::StreamHellosStream>, Status> {
+            Ok(Response::new(tokio_stream::empty()))
+        }
 
-impl GrpcResponseCache {
-    pub fn get(&self, key: &GrpcCacheKey) -> Option<GqlValue> {
-        self.inner.lock().ok().and_then(|map| map.get(key).cloned())
-    }
+        async fn resolve_user(
+            &self,
+            request: Request<GetUserRequest>,
+        ) -> std::result::Result<Response<User>, Status> {
+            Ok(Response::new(test_user(request.into_inner().id)))
+        }
 
-    pub fn insert(&self, key: GrpcCacheKey, value: GqlValue) {
-        if let Ok(mut map) = self.inner.lock() {
-            map.insert(key, value);
+        async fn update_user(
+            &self,
+            request: Request<User>,
+        ) -> std::result::Result<Response<User>, Status> {
+            Ok(Response::new(request.into_inner()))
+        }
+
+        async fn upload_avatar(
+            &self,
+            _request: Request<UploadAvatarRequest>,
+        ) -> std::result::Result<Response<UploadAvatarReply>, Status> {
+            Err(Status::unimplemented("upload_avatar"))
+        }
+
+        async fn upload_avatars(
+            &self,
+            _request: Request<UploadAvatarsRequest>,
+        ) -> std::result::Result<Response<UploadAvatarsReply>, Status> {
+            Err(Status::unimplemented("upload_avatars"))
         }
     }
-}
 
-#[derive(Hash, Eq, PartialEq, Clone)]
-pub struct GrpcCacheKey {
-    service: String,
-    path: String,
-    request: Vec<u8>,
-}
+    pub(super) fn test_build_ctx(pool: &DescriptorPool) -> BuildContext {
+        BuildContext {
+            field_ext: pool
+                .get_extension_by_name("graphql.field")
+                .expect("graphql.field extension"),
+            behavior: pool.get_extension_by_name("google.api.field_behavior"),
+            naming: Arc::new(ProtoNaming),
+            patch: false,
+        }
+    }
 
-impl GrpcCacheKey {
-    pub fn new(
-        service: &str,
-        path: &str,
-        request: &DynamicMessage,
-    ) -> std::result::Result<Self, prost::EncodeError> {
-        Ok(Self {
-            service: service.to_string(),
-            path: path.to_string(),
-            request: request.encode_to_vec(),
-        })
+    async fn spawn_greeter() -> GrpcClientPool {
+        spawn_backend(
+            tonic::transport::Server::builder().add_service(GreeterServer::new(TestGreeter)),
+            &["greeter.Greeter"],
+        )
+        .await
     }
-}
 
-impl TypeRegistry {
-    fn type_name_for_message(desc: &MessageDescriptor) -> String {
-        desc.full_name().replace('.', "_")
+    /// Serve `router` on a free local port and return a pool that routes `services` to it.
+    pub(super) async fn spawn_backend(
+        router: tonic::transport::server::Router,
+        services: &[&str],
+    ) -> GrpcClientPool {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("listener address");
+        let incoming = tonic::transport::server::TcpIncoming::from_listener(listener, true, None)
+            .expect("incoming stream");
+        tokio::spawn(router.serve_with_incoming(incoming));
+
+        let pool = GrpcClientPool::new();
+        for service in services {
+            pool.add(
+                *service,
+                GrpcClient::builder(format!("http://{addr}"))
+                    .connect_lazy()
+                    .expect("lazy client"),
+            );
+        }
+        pool
     }
 
-    fn type_name_for_enum(desc: &EnumDescriptor) -> String {
-        desc.full_name().replace('.', "_")
+    pub(crate) fn build_schema(descriptor: &[u8], pool: &GrpcClientPool) -> DynamicSchema {
+        SchemaBuilder::new()
+            .with_descriptor_set_bytes(descriptor)
+            .build(pool)
+            .expect("schema builds")
     }
 
-    fn ensure_enum(&mut self, desc: &EnumDescriptor) -> TypeRef {
-        let name = Self::type_name_for_enum(desc);
-        if !self.enums.contains_key(&name) {
-            let mut en = Enum::new(name.clone());
-            for value in desc.values() {
-                en = en.item(EnumItem::new(value.name()));
+    /// Introspect the fields of type `name`, keyed by field name, as
+    /// definitions such as `hello(name: String): greeter_HelloReply`.
+    pub(crate) async fn type_fields(
+        schema: &DynamicSchema,
+        name: &str,
+    ) -> BTreeMap<String, String> {
+        fn render(ty: &serde_json::Value) -> String {
+            match ty["kind"].as_str() {
+                Some("NON_NULL") => format!("{}!", render(&ty["ofType"])),
+                Some("LIST") => format!("[{}]", render(&ty["ofType"])),
+                _ => ty["name"].as_str().expect("named type").to_owned(),
             }
-            self.enums.insert(name.clone(), en);
         }
-        TypeRef::named(name)
+
+        let response = schema
+            .execute(async_graphql::Request::new(format!(
+                r#"{{ __type(name: "{name}") {{
+                    fields(includeDeprecated: true) {{ name args {{ name type {{ ...Ref }} }} type {{ ...Ref }} }}
+                    inputFields {{ name type {{ ...Ref }} }}
+                }} }}
+                fragment Ref on __Type {{ kind name ofType {{ kind name ofType {{ kind name ofType {{ kind name }} }} }} }}"#
+            )))
+            .await;
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        let ty = &data["__type"];
+        let fields = ty["fields"]
+            .as_array()
+            .or_else(|| ty["inputFields"].as_array())
+            .unwrap_or_else(|| panic!("{name} has fields"));
+        fields
+            .iter()
+            .map(|field| {
+                let name = field["name"].as_str().expect("field name").to_owned();
+                let args: Vec<_> = field["args"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|arg| {
+                        format!(
+                            "{}: {}",
+                            arg["name"].as_str().expect("argument name"),
+                            render(&arg["type"])
+                        )
+                    })
+                    .collect();
+                let definition = if args.is_empty() {
+                    format!("{name}: {}", render(&field["type"]))
+                } else {
+                    format!("{name}({}): {}", args.join(", "), render(&field["type"]))
+                };
+                (name, definition)
+            })
+            .collect()
     }
 
-    fn ensure_input_object(
-        &mut self,
-        message: &MessageDescriptor,
-        field_ext: &ExtensionDescriptor,
-    ) -> TypeRef {
-        let name = Self::type_name_for_message(message);
-        if self.input_objects.contains_key(&name) {
-            return TypeRef::named(name);
-        }
+    /// Shared fixtures of the feature tests: `types.proto`, `annotations.proto`,
+    /// `operations.proto` and `inventory.proto`, compiled into one descriptor set.
+    pub(super) const FIXTURES_DESCRIPTOR: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/fixtures_descriptor.bin"));
 
-        let mut input = InputObject::new(name.clone());
-        for field in message.fields() {
-            if field_is_omitted(&field, &field_ext) {
-                continue;
-            }
+    #[allow(clippy::all)]
+    pub(super) mod types {
+        include!(concat!(env!("OUT_DIR"), "/types.rs"));
+    }
 
-            let field_name = graphql_field_name(&field, field_ext);
-            let required = field_is_required(&field, field_ext);
-            let ty = self.input_type_for_field(&field, field_ext, required);
-            input = input.field(InputValue::new(field_name, ty));
-        }
+    #[allow(clippy::all)]
+    pub(super) mod annotations {
+        include!(concat!(env!("OUT_DIR"), "/annotations.rs"));
+    }
 
-        self.input_objects.insert(name.clone(), input);
-        TypeRef::named(name)
+    #[allow(clippy::all)]
+    pub(super) mod operations {
+        include!(concat!(env!("OUT_DIR"), "/operations.rs"));
     }
 
-    fn ensure_object(
-        &mut self,
-        message: &MessageDescriptor,
-        field_ext: &ExtensionDescriptor,
-    ) -> TypeRef {
-        let field_ext = field_ext.clone();
-        let name = Self::type_name_for_message(message);
-        if self.objects.contains_key(&name) {
-            return TypeRef::named(name);
-        }
+    /// A builder over the fixtures that only exposes `services`, as the fixture packages
+    /// reuse root field names.
+    pub(super) fn fixture_builder(services: &[&str]) -> SchemaBuilder {
+        SchemaBuilder::new()
+            .with_descriptor_set_bytes(FIXTURES_DESCRIPTOR)
+            .with_services(services.iter().copied())
+    }
 
-        let mut obj = Object::new(name.clone());
-        for field in message.fields() {
-            if field_is_omitted(&field, &field_ext) {
-                continue;
-            }
+    pub(super) fn fixture_schema(services: &[&str], pool: &GrpcClientPool) -> DynamicSchema {
+        fixture_builder(services)
+            .build(pool)
+            .expect("schema builds")
+    }
 
-            let field_name = graphql_field_name(&field, &field_ext);
-            let required = field_is_required(&field, &field_ext);
-            let ty = self.output_type_for_field(&field, &field_ext, required);
-            let field_desc = field.clone();
-            let field_name_for_value = field_name.clone();
-            let field_ext_for_resolver = field_ext.clone();
+    /// Serve the fixture backends and return a pool that routes the fixture services to
+    /// them. `annotations.Greeter` has no backend.
+    pub(super) async fn spawn_fixtures() -> GrpcClientPool {
+        spawn_backend(
+            tonic::transport::Server::builder()
+                .add_service(types::types_server::TypesServer::new(TestTypes))
+                .add_service(annotations::printer_server::PrinterServer::new(
+                    TestAnnotations,
+                ))
+                .add_service(annotations::library_server::LibraryServer::new(
+                    TestAnnotations,
+                ))
+                .add_service(operations::operations_server::OperationsServer::new(
+                    TestOperations::default(),
+                )),
+            &[
+                "types.Types",
+                "annotations.Printer",
+                "annotations.Library",
+                "operations.Operations",
+            ],
+        )
+        .await
+    }
 
-            let mut gql_field = Field::new(field_name.clone(), ty, move |ctx| {
-                let field_ext = field_ext_for_resolver.clone();
-                let field_desc = field_desc.clone();
-                let field_name_for_value = field_name_for_value.clone();
-                FieldFuture::new(async move {
-                    if let Some(parent) = ctx.parent_value.downcast_ref::<DynamicMessage>() {
-                        let value = parent.get_field(&field_desc);
-                        let gql_value =
-                            prost_value_to_graphql(&value, Some(&field_desc), &field_ext)
-                                .map_err(|e| async_graphql::Error::new(e.to_string()))?;
-                        return Ok(Some(gql_value));
-                    }
+    /// `types.Types` backend, echoing what it receives.
+    pub(super) struct TestTypes;
 
-                    if let Some(GqlValue::Object(map)) = ctx.parent_value.as_value() {
-                        if let Some(val) = map.get(&Name::new(field_name_for_value.clone())) {
-                            return Ok(Some(val.clone()));
-                        }
-                    }
+    #[tonic::async_trait]
+    impl types::types_server::Types for TestTypes {
+        async fn echo_counters(
+            &self,
+            request: Request<types::Counters>,
+        ) -> std::result::Result<Response<types::Counters>, Status> {
+            Ok(Response::new(request.into_inner()))
+        }
 
-                    Ok(Some(GqlValue::Null))
-                })
-            });
+        async fn label(
+            &self,
+            request: Request<types::LabelRequest>,
+        ) -> std::result::Result<Response<types::Labeled>, Status> {
+            let req = request.into_inner();
+            Ok(Response::new(types::Labeled {
+                name: req.name,
+                labels: req.labels,
+            }))
+        }
 
-            // Apply federation field directives
-            if field_is_external(&field, &field_ext) {
-                gql_field = gql_field.directive(async_graphql::dynamic::Directive::new("external"));
-            }
-            if let Some(requires) = field_requires(&field, &field_ext) {
+        async fn greet(
+            &self,
+            request: Request<types::GreetRequest>,
+        ) -> std::result::Result<Response<types::GreetReply>, Status> {
+            let req = request.into_inner();
+            let target = match req.target.and_then(|target| target.target) {
+                Some(types::greet_target::Target::UserId(id)) => id,
+                Some(types::greet_target::Target::Email(email)) => email,
+                None => "nobody".to_string(),
+            };
+            let style = req.style.map(|style| match style {
+                types::greet_request::Style::Formal(formal) => {
+                    types::greet_reply::Style::Formal(formal)
+                }
+                types::greet_request::Style::Nickname(nick) => {
+                    types::greet_reply::Style::Nickname(nick)
+                }
+            });
+            Ok(Response::new(types::GreetReply {
+                message: format!("Hello, {target}!"),
+                style,
+                tone: req.tone,
+                sent_at: req.sent_at,
+            }))
+        }
+
+        async fn echo_stats(
+            &self,
+            request: Request<types::Stats>,
+        ) -> std::result::Result<Response<types::Stats>, Status> {
+            Ok(Response::new(request.into_inner()))
+        }
+    }
+
+    /// `annotations.Printer` and `annotations.Library` backend.
+    pub(super) struct TestAnnotations;
+
+    #[tonic::async_trait]
+    impl annotations::printer_server::Printer for TestAnnotations {
+        async fn print(
+            &self,
+            request: Request<annotations::PrintRequest>,
+        ) -> std::result::Result<Response<annotations::PrintReply>, Status> {
+            let req = request.into_inner();
+            Ok(Response::new(annotations::PrintReply {
+                text: format!("Hello, {}!", req.text),
+                copies: req.copies,
+            }))
+        }
+    }
+
+    #[tonic::async_trait]
+    impl annotations::library_server::Library for TestAnnotations {
+        async fn get_book(
+            &self,
+            _request: Request<annotations::GetBookRequest>,
+        ) -> std::result::Result<Response<annotations::Book>, Status> {
+            Err(Status::unimplemented("get_book"))
+        }
+
+        async fn create_book(
+            &self,
+            _request: Request<annotations::CreateBookRequest>,
+        ) -> std::result::Result<Response<annotations::Book>, Status> {
+            Err(Status::unimplemented("create_book"))
+        }
+
+        /// Echoes the book with the update mask paths as its note.
+        async fn update_book(
+            &self,
+            request: Request<annotations::UpdateBookRequest>,
+        ) -> std::result::Result<Response<annotations::Book>, Status> {
+            let req = request.into_inner();
+            let paths = req.update_mask.map(|mask| mask.paths).unwrap_or_default();
+            Ok(Response::new(annotations::Book {
+                note: paths.join(","),
+                ..req.book.unwrap_or_default()
+            }))
+        }
+
+        async fn update_books(
+            &self,
+            _request: Request<tonic::Streaming<annotations::UpdateBookRequest>>,
+        ) -> std::result::Result<Response<annotations::UpdateBooksReply>, Status> {
+            Err(Status::unimplemented("update_books"))
+        }
+    }
+
+    /// `operations.Operations` backend, counting the user lookups, batched or not.
+    #[derive(Clone, Default)]
+    pub(super) struct TestOperations {
+        pub(super) user_calls: Arc<AtomicUsize>,
+    }
+
+    fn test_author(id: String) -> operations::User {
+        operations::User {
+            display_name: format!("User {id}"),
+            id,
+            trusted: true,
+        }
+    }
+
+    fn test_hello(name: &str) -> operations::HelloReply {
+        operations::HelloReply {
+            message: format!("Hello, {name}!"),
+        }
+    }
+
+    #[tonic::async_trait]
+    impl operations::operations_server::Operations for TestOperations {
+        async fn get_user(
+            &self,
+            request: Request<operations::GetUserRequest>,
+        ) -> std::result::Result<Response<operations::User>, Status> {
+            self.user_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Response::new(test_author(request.into_inner().id)))
+        }
+
+        async fn batch_get_users(
+            &self,
+            request: Request<operations::BatchGetUsersRequest>,
+        ) -> std::result::Result<Response<operations::BatchGetUsersReply>, Status> {
+            self.user_calls.fetch_add(1, Ordering::SeqCst);
+            let users = request
+                .into_inner()
+                .ids
+                .into_iter()
+                .map(test_author)
+                .collect();
+            Ok(Response::new(operations::BatchGetUsersReply { users }))
+        }
+
+        async fn list_reviews(
+            &self,
+            request: Request<operations::ListReviewsRequest>,
+        ) -> std::result::Result<Response<operations::ListReviewsReply>, Status> {
+            let reviews = request
+                .into_inner()
+                .author_ids
+                .into_iter()
+                .map(|id| operations::Review {
+                    body: format!("Review by {id}"),
+                    author: Some(operations::User {
+                        id,
+                        ..Default::default()
+                    }),
+                })
+                .collect();
+            Ok(Response::new(operations::ListReviewsReply { reviews }))
+        }
+
+        async fn search_books(
+            &self,
+            request: Request<operations::SearchBooksRequest>,
+        ) -> std::result::Result<Response<operations::SearchBooksReply>, Status> {
+            let req = request.into_inner();
+            let title = req.filter.map(|filter| filter.title).unwrap_or_default();
+            let books = (0..req.limit)
+                .map(|_| operations::Book {
+                    title: title.clone(),
+                })
+                .collect();
+            Ok(Response::new(operations::SearchBooksReply { books }))
+        }
+
+        /// Pages through five books by the author, two per page by default.
+        async fn list_books(
+            &self,
+            request: Request<operations::ListBooksRequest>,
+        ) -> std::result::Result<Response<operations::ListBooksReply>, Status> {
+            const TOTAL: usize = 5;
+            let req = request.into_inner();
+            let start: usize = req.page_token.parse().unwrap_or(0);
+            let size = if req.page_size > 0 {
+                req.page_size as usize
+            } else {
+                2
+            };
+            let end = (start + size).min(TOTAL);
+            Ok(Response::new(operations::ListBooksReply {
+                books: (start..end)
+                    .map(|idx| operations::Book {
+                        title: format!("{} #{idx}", req.author),
+                    })
+                    .collect(),
+                next_page_token: if end < TOTAL {
+                    end.to_string()
+                } else {
+                    String::new()
+                },
+            }))
+        }
+
+        /// Echoes the requested read mask paths as the bio.
+        async fn get_profile(
+            &self,
+            request: Request<operations::GetProfileRequest>,
+        ) -> std::result::Result<Response<operations::Profile>, Status> {
+            let req = request.into_inner();
+            let paths = req.read_mask.map(|mask| mask.paths).unwrap_or_default();
+            Ok(Response::new(operations::Profile {
+                user: Some(test_author(req.user_id.clone())),
+                bio: paths.join(","),
+                last_greeting: Some(operations::Greeting {
+                    message: format!("Hello, {}!", req.user_id),
+                    meta: Some(operations::GreetingMeta {
+                        from: Some(operations::User {
+                            id: req.user_id,
+                            ..Default::default()
+                        }),
+                    }),
+                }),
+                contact: None,
+            }))
+        }
+
+        /// Echoes the patch with the update mask paths as the bio.
+        async fn update_profile(
+            &self,
+            request: Request<operations::UpdateProfileRequest>,
+        ) -> std::result::Result<Response<operations::Profile>, Status> {
+            let req = request.into_inner();
+            let paths = req.update_mask.map(|mask| mask.paths).unwrap_or_default();
+            Ok(Response::new(operations::Profile {
+                bio: paths.join(","),
+                ..req.profile.unwrap_or_default()
+            }))
+        }
+
+        async fn collect_hellos(
+            &self,
+            request: Request<tonic::Streaming<operations::HelloRequest>>,
+        ) -> std::result::Result<Response<operations::HelloManyReply>, Status> {
+            let mut requests = request.into_inner();
+            let mut replies = Vec::new();
+            while let Some(req) = requests.message().await? {
+                replies.push(test_hello(&req.name));
+            }
+            Ok(Response::new(operations::HelloManyReply { replies }))
+        }
+
+        type ChatStream = std::pin::Pin<
+            Box<
+                dyn tokio_stream::Stream<Item = std::result::Result<operations::HelloReply, Status>>
+                    + Send,
+            >,
+        >;
+
+        // tonic streams yield `Result<_, Status>`.
+        #[allow(clippy::result_large_err)]
+        async fn chat(
+            &self,
+            request: Request<tonic::Streaming<operations::HelloRequest>>,
+        ) -> std::result::Result<Response<Self::ChatStream>, Status> {
+            let stream = request
+                .into_inner()
+                .map(|req| req.map(|req| test_hello(&req.name)));
+            Ok(Response::new(Box::pin(stream)))
+        }
+
+        async fn upload_chunks(
+            &self,
+            request: Request<tonic::Streaming<operations::Chunk>>,
+        ) -> std::result::Result<Response<operations::UploadReply>, Status> {
+            let mut chunks = request.into_inner();
+            let mut size = 0;
+            while let Some(chunk) = chunks.message().await? {
+                size += chunk.data.len() as u64;
+            }
+            Ok(Response::new(operations::UploadReply { size }))
+        }
+    }
+
+    #[tokio::test]
+    async fn nested_resolver_calls_target_rpc() {
+        let pool = spawn_greeter().await;
+        let schema = build_schema(GREETER_DESCRIPTOR, &pool);
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{ hello(name: "Ada") { message meta { correlationId from { id displayName trusted } } } }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["hello"]["meta"]["from"],
+            serde_json::json!({ "id": "demo", "displayName": "User demo", "trusted": true })
+        );
+        assert_eq!(data["hello"]["meta"]["correlationId"], "hello-Ada");
+    }
+
+    #[tokio::test]
+    async fn clients_are_only_created_for_exposed_services() {
+        let client_pool = GrpcClientPool::new();
+        SchemaBuilder::new()
+            .with_descriptor_set_bytes(FEDERATION_DESCRIPTOR)
+            .with_services(["federation_example.UserService"])
+            .build(&client_pool)
+            .expect("schema builds");
+
+        assert!(client_pool.get("federation_example.UserService").is_some());
+        assert!(client_pool
+            .get("federation_example.ProductService")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn input_objects_get_their_own_type_names() {
+        let pool = spawn_greeter().await;
+        let schema = build_schema(GREETER_DESCRIPTOR, &pool);
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"mutation {
+                    updateUser(input: { id: "ada", displayName: "Ada", trusted: true }) {
+                        id displayName trusted
+                    }
+                }"#,
+            ))
+            .await;
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(data["updateUser"]["displayName"], "Ada");
+
+        let types = schema
+            .execute(async_graphql::Request::new(
+                r#"{ input: __type(name: "greeter_UserInput") { kind } output: __type(name: "greeter_User") { kind } }"#,
+            ))
+            .await
+            .data
+            .into_json()
+            .expect("valid JSON response");
+        assert_eq!(types["input"]["kind"], "INPUT_OBJECT");
+        assert_eq!(types["output"]["kind"], "OBJECT");
+
+        let err = SchemaBuilder::new()
+            .with_descriptor_set_bytes(GREETER_DESCRIPTOR)
+            .with_input_type_suffix("")
+            .build(&pool)
+            .err()
+            .expect("input and output greeter.User collide");
+        assert!(
+            err.to_string()
+                .contains("greeter_User is generated for both"),
+            "unexpected error: {err}"
+        );
+    }
+
+    struct TestComments;
+
+    #[tonic::async_trait]
+    impl recursive::comments_server::Comments for TestComments {
+        async fn post_comment(
+            &self,
+            request: Request<recursive::Comment>,
+        ) -> std::result::Result<Response<recursive::Comment>, Status> {
+            Ok(Response::new(request.into_inner()))
+        }
+
+        async fn get_thread(
+            &self,
+            request: Request<recursive::GetThreadRequest>,
+        ) -> std::result::Result<Response<recursive::Thread>, Status> {
+            let comment = |id: &str, replies| recursive::Comment {
+                id: id.to_string(),
+                body: format!("comment {id}"),
+                replies,
+                thread: None,
+            };
+            Ok(Response::new(recursive::Thread {
+                title: request.into_inner().title,
+                root: Some(Box::new(comment(
+                    "1",
+                    vec![comment("2", vec![comment("3", vec![])])],
+                ))),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn recursive_messages_build_and_resolve() {
+        let pool = spawn_backend(
+            tonic::transport::Server::builder().add_service(
+                recursive::comments_server::CommentsServer::new(TestComments),
+            ),
+            &["recursive.Comments"],
+        )
+        .await;
+
+        let schema = SchemaBuilder::new()
+            .with_descriptor_set_bytes(RECURSIVE_DESCRIPTOR)
+            .build(&pool)
+            .expect("recursive schema builds");
+
+        let query = schema
+            .execute(async_graphql::Request::new(
+                r#"{ thread(title: "Rust") { title root { id replies { id replies { body } } } } }"#,
+            ))
+            .await;
+        assert!(query.errors.is_empty(), "errors: {:?}", query.errors);
+        let data = query.data.into_json().expect("valid JSON response");
+        assert_eq!(data["thread"]["title"], "Rust");
+        assert_eq!(
+            data["thread"]["root"]["replies"][0]["replies"][0]["body"],
+            "comment 3"
+        );
+
+        let mutation = schema
+            .execute(async_graphql::Request::new(
+                r#"mutation {
+                    postComment(input: {
+                        id: "1"
+                        replies: [{ id: "2", replies: [{ id: "3" }] }]
+                        thread: { title: "Rust", root: { id: "0" } }
+                    }) {
+                        replies { replies { id } }
+                        thread { title root { id } }
+                    }
+                }"#,
+            ))
+            .await;
+        assert!(mutation.errors.is_empty(), "errors: {:?}", mutation.errors);
+        let data = mutation.data.into_json().expect("valid JSON response");
+        assert_eq!(data["postComment"]["replies"][0]["replies"][0]["id"], "3");
+        assert_eq!(data["postComment"]["thread"]["title"], "Rust");
+        assert_eq!(data["postComment"]["thread"]["root"]["id"], "0");
+    }
+
+    #[tokio::test]
+    async fn resolver_methods_are_not_exposed_on_query() {
+        let schema = build_schema(GREETER_DESCRIPTOR, &GrpcClientPool::new());
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{ __type(name: "Query") { fields { name } } }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        let fields: Vec<_> = data["__type"]["fields"]
+            .as_array()
+            .expect("query fields")
+            .iter()
+            .filter_map(|f| f["name"].as_str())
+            .collect();
+
+        assert!(fields.contains(&"hello"), "query fields: {fields:?}");
+        assert!(!fields.contains(&"user"), "query fields: {fields:?}");
+    }
+
+    #[tokio::test]
+    async fn federation_adds_entities_query() {
+        let schema = SchemaBuilder::new()
+            .with_descriptor_set_bytes(FEDERATION_DESCRIPTOR)
+            .enable_federation()
+            .build(&GrpcClientPool::new())
+            .expect("schema builds");
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                "{ _entities(representations: []) { __typename } }",
+            ))
+            .await;
+
+        assert!(
+            response.errors.is_empty(),
+            "expected _entities field to exist, got errors: {:?}",
+            response.errors
+        );
+
+        let data = response.data.into_json().expect("valid JSON response");
+        let entities = data
+            .get("_entities")
+            .and_then(|value| value.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        assert!(entities.is_empty(), "expected empty entities list");
+    }
+
+    #[tokio::test]
+    async fn sdl_export_is_sorted_and_stable() {
+        let build = || build_schema(GREETER_DESCRIPTOR, &GrpcClientPool::new());
+        let sdl = build().sdl();
+        assert_eq!(sdl, build().sdl());
+
+        let document = async_graphql::parser::parse_schema(&sdl).expect("SDL parses");
+        let mut types = Vec::new();
+        for definition in document.definitions {
+            let TypeSystemDefinition::Type(ty) = definition else {
+                continue;
+            };
+            if let TypeKind::Object(object) = &ty.node.kind {
+                let fields: Vec<_> = object.fields.iter().map(|f| &f.node.name.node).collect();
+                assert!(
+                    fields.is_sorted(),
+                    "{} fields: {fields:?}",
+                    ty.node.name.node
+                );
+            }
+            types.push(ty.node.name.node.clone());
+        }
+        assert!(types.is_sorted(), "types: {types:?}");
+
+        let federation = || {
+            SchemaBuilder::new()
+                .with_descriptor_set_bytes(FEDERATION_DESCRIPTOR)
+                .enable_federation()
+                .build(&GrpcClientPool::new())
+                .expect("schema builds")
+                .subgraph_sdl()
+        };
+        let subgraph = federation();
+        assert_eq!(subgraph, federation());
+        assert!(subgraph.contains("extend schema @link"), "{subgraph}");
+        assert!(subgraph.contains("@key(fields: \"id\")"), "{subgraph}");
+        assert!(!subgraph.contains("_entities"), "{subgraph}");
+    }
+
+    /// Reflection server answering with the files as compiled, one file per request.
+    /// `tonic_reflection`'s own server re-encodes files through `prost_types`, which drops
+    /// custom options such as `graphql.schema`.
+    #[derive(Clone)]
+    struct TestReflection {
+        pool: DescriptorPool,
+    }
+
+    impl TestReflection {
+        fn answer(
+            &self,
+            request: &tonic_reflection::pb::v1::ServerReflectionRequest,
+        ) -> tonic_reflection::pb::v1::server_reflection_response::MessageResponse {
+            use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+            use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+            use tonic_reflection::pb::v1::{
+                ErrorResponse, FileDescriptorResponse, ListServiceResponse, ServiceResponse,
+            };
+
+            let file = match &request.message_request {
+                Some(MessageRequest::ListServices(_)) => {
+                    return MessageResponse::ListServicesResponse(ListServiceResponse {
+                        service: self
+                            .pool
+                            .services()
+                            .map(|service| ServiceResponse {
+                                name: service.full_name().to_string(),
+                            })
+                            .collect(),
+                    })
+                }
+                Some(MessageRequest::FileContainingSymbol(symbol)) => self
+                    .pool
+                    .get_service_by_name(symbol)
+                    .map(|service| service.parent_file()),
+                Some(MessageRequest::FileByFilename(name)) => self.pool.get_file_by_name(name),
+                _ => None,
+            };
+            match file {
+                Some(file) => MessageResponse::FileDescriptorResponse(FileDescriptorResponse {
+                    file_descriptor_proto: vec![file.encode_to_vec()],
+                }),
+                None => MessageResponse::ErrorResponse(ErrorResponse {
+                    error_code: tonic::Code::NotFound as i32,
+                    error_message: "not found".to_string(),
+                }),
+            }
+        }
+    }
+
+    #[tonic::async_trait]
+    impl tonic_reflection::pb::v1::server_reflection_server::ServerReflection for TestReflection {
+        type ServerReflectionInfoStream = std::pin::Pin<
+            Box<
+                dyn tokio_stream::Stream<
+                        Item = std::result::Result<
+                            tonic_reflection::pb::v1::ServerReflectionResponse,
+                            Status,
+                        >,
+                    > + Send,
+            >,
+        >;
+
+        // tonic streams yield `Result<_, Status>`.
+        #[allow(clippy::result_large_err)]
+        async fn server_reflection_info(
+            &self,
+            request: Request<tonic::Streaming<tonic_reflection::pb::v1::ServerReflectionRequest>>,
+        ) -> std::result::Result<Response<Self::ServerReflectionInfoStream>, Status> {
+            let server = self.clone();
+            let stream = request.into_inner().map(move |request| {
+                let request = request?;
+                Ok(tonic_reflection::pb::v1::ServerReflectionResponse {
+                    valid_host: String::new(),
+                    message_response: Some(server.answer(&request)),
+                    original_request: Some(request),
+                })
+            });
+            Ok(Response::new(Box::pin(stream)))
+        }
+    }
+
+    #[tokio::test]
+    async fn discovers_descriptors_through_server_reflection() {
+        let reflection = TestReflection {
+            pool: DescriptorPool::decode(GREETER_DESCRIPTOR).expect("greeter descriptors"),
+        };
+        let pool = spawn_backend(
+            tonic::transport::Server::builder()
+                .add_service(
+                    tonic_reflection::pb::v1::server_reflection_server::ServerReflectionServer::new(
+                        reflection,
+                    ),
+                )
+                .add_service(GreeterServer::new(TestGreeter)),
+            &["greeter.Greeter"],
+        )
+        .await;
+        let schema = SchemaBuilder::new()
+            .with_server_reflection(&pool)
+            .await
+            .expect("descriptors discovered")
+            .build(&pool)
+            .expect("schema builds");
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{ hello(name: "Ada") { message } }"#,
+            ))
+            .await;
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(data["hello"]["message"], "Hello, Ada!");
+    }
+
+    #[tokio::test]
+    async fn reflection_reports_descriptors_without_graphql_options() {
+        // tonic_reflection re-encodes the files through prost_types, dropping custom options.
+        let reflection = || {
+            tonic_reflection::server::Builder::configure()
+                .register_encoded_file_descriptor_set(GREETER_DESCRIPTOR)
+        };
+        let v1 = spawn_backend(
+            tonic::transport::Server::builder()
+                .add_service(reflection().build_v1().expect("v1 reflection")),
+            &["greeter.Greeter"],
+        )
+        .await;
+        // Servers that only implement v1alpha are asked through it.
+        let v1alpha = spawn_backend(
+            tonic::transport::Server::builder()
+                .add_service(reflection().build_v1alpha().expect("v1alpha reflection")),
+            &["greeter.Greeter"],
+        )
+        .await;
+
+        for pool in [v1, v1alpha] {
+            let err = SchemaBuilder::new()
+                .with_server_reflection(&pool)
+                .await
+                .err()
+                .expect("stripped options are reported");
+            assert!(
+                err.to_string().contains("no graphql annotations found"),
+                "{err}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn merges_descriptor_sets_and_reports_conflicts() {
+        // Both sets contain graphql.proto; the greeter set is even added twice.
+        let schema = SchemaBuilder::new()
+            .with_descriptor_set_bytes(GREETER_DESCRIPTOR)
+            .with_descriptor_set_bytes(RECURSIVE_DESCRIPTOR)
+            .with_descriptor_set_bytes(GREETER_DESCRIPTOR)
+            .build(&GrpcClientPool::new())
+            .expect("merged schema builds");
+        let query = type_fields(&schema, "Query").await;
+        assert!(query.contains_key("hello"), "{query:?}");
+        assert!(query.contains_key("thread"), "{query:?}");
+
+        let err = SchemaBuilder::new()
+            .with_descriptor_set_bytes(include_bytes!(concat!(
+                env!("OUT_DIR"),
+                "/compat_v1/catalog_descriptor.bin"
+            )))
+            .with_descriptor_set_bytes(include_bytes!(concat!(
+                env!("OUT_DIR"),
+                "/compat_v2/catalog_descriptor.bin"
+            )))
+            .build(&GrpcClientPool::new())
+            .err()
+            .expect("conflicting files fail the build");
+        assert!(
+            err.to_string()
+                .contains("catalog.proto differs between descriptor set #1 and descriptor set #2"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_exposes_unannotated_methods_by_convention() {
+        let builder = SchemaBuilder::new()
+            .with_descriptor_set_bytes(GREETER_DESCRIPTOR)
+            .with_descriptor_set_bytes(FIXTURES_DESCRIPTOR)
+            .with_services(["greeter.Greeter", "inventory.Inventory"]);
+        let schema = builder
+            .clone()
+            .build(&GrpcClientPool::new())
+            .expect("schema builds");
+        let query = type_fields(&schema, "Query").await;
+        assert!(!query.contains_key("getItem"), "{query:?}");
+
+        let schema = builder
+            .with_auto_expose(true)
+            .build(&GrpcClientPool::new())
+            .expect("schema builds");
+        let query = type_fields(&schema, "Query").await;
+        let mutation = type_fields(&schema, "Mutation").await;
+        let subscription = type_fields(&schema, "Subscription").await;
+
+        assert_eq!(query["getItem"], "getItem(id: String): inventory_Item");
+        assert!(query.contains_key("listItems"), "{query:?}");
+        assert!(mutation.contains_key("reserveItem"), "{mutation:?}");
+        assert!(mutation.contains_key("getaway"), "{mutation:?}");
+        assert!(subscription.contains_key("watchItem"), "{subscription:?}");
+        assert!(!mutation.contains_key("importItems"), "{mutation:?}");
+        // The annotated greeter query keeps the name.
+        assert_eq!(query["hello"], "hello(name: String): greeter_HelloReply!");
+    }
+}
+
+/// Generated types, kept sorted by name so they are registered (and exported) in a
+/// stable order.
+struct TypeRegistry {
+    objects: BTreeMap<String, Object>,
+    input_objects: BTreeMap<String, InputObject>,
+    enums: BTreeMap<String, Enum>,
+    client_pool: GrpcClientPool,
+    /// RESOLVER methods, keyed by both their GraphQL name and `package.Service/Method`.
+    resolvers: HashMap<String, (MethodDescriptor, GraphqlSchema)>,
+    maps: MapRepresentation,
+    descriptions: bool,
+    /// Custom scalars used by some field, so they are only registered when needed.
+    scalars: HashSet<&'static str>,
+    naming: Arc<dyn NamingStrategy>,
+    input_type_suffix: String,
+    /// Every GraphQL type name handed out so far, with what it was generated for.
+    type_names: HashMap<String, String>,
+    /// Source locations (comments) of every file, indexed by path once per file.
+    locations: HashMap<String, HashMap<Vec<i32>, Location>>,
+    /// Whether some nested resolver batches its loads (`response.batch_key`).
+    batched: bool,
+    /// Services targeted by nested resolvers, which need a client even outside the
+    /// allowlist.
+    resolver_services: HashSet<String>,
+}
+
+/// Per-request memoization to avoid duplicate gRPC calls for identical inputs.
+///
+/// The gateway memoizes raw response messages, which nested resolvers read fields from;
+/// [`get`](Self::get)/[`insert`](Self::insert) keep GraphQL values stored by callers.
+#[derive(Clone, Default)]
+pub struct GrpcResponseCache {
+    inner: Arc<Mutex<HashMap<GrpcCacheKey, GqlValue>>>,
+    messages: Arc<Mutex<HashMap<GrpcCacheKey, DynamicMessage>>>,
+}
+
+impl GrpcResponseCache {
+    pub fn get(&self, key: &GrpcCacheKey) -> Option<GqlValue> {
+        self.inner.lock().ok().and_then(|map| map.get(key).cloned())
+    }
+
+    pub fn insert(&self, key: GrpcCacheKey, value: GqlValue) {
+        if let Ok(mut map) = self.inner.lock() {
+            map.insert(key, value);
+        }
+    }
+
+    fn get_message(&self, key: &GrpcCacheKey) -> Option<DynamicMessage> {
+        self.messages
+            .lock()
+            .ok()
+            .and_then(|map| map.get(key).cloned())
+    }
+
+    fn insert_message(&self, key: GrpcCacheKey, value: DynamicMessage) {
+        if let Ok(mut map) = self.messages.lock() {
+            map.insert(key, value);
+        }
+    }
+}
+
+/// Batches nested resolver loads (`response.batch_key`) for a [`DataLoader`].
+///
+/// The data loader collects the keys loaded during a short delay, so loads from sibling
+/// fields, other list items and other depths share one RPC per resolver.
+pub(crate) struct GrpcBatchLoader {
+    client_pool: GrpcClientPool,
+}
+
+/// Gives every request its own [`GrpcBatchLoader`], so batching does not depend on how the
+/// schema is executed.
+#[derive(Clone)]
+struct BatchLoaderExtension {
+    client_pool: GrpcClientPool,
+    batch_delay: Duration,
+}
+
+impl ExtensionFactory for BatchLoaderExtension {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for BatchLoaderExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: async_graphql::Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<async_graphql::Request> {
+        let loader = DataLoader::new(GrpcBatchLoader::new(self.client_pool.clone()), tokio::spawn)
+            .delay(self.batch_delay);
+        next.run(ctx, request.data(loader)).await
+    }
+}
+
+/// A batched nested load: the resolver it goes through and the parent's key.
+#[derive(Clone)]
+struct BatchLoad {
+    resolver: NestedResolver,
+    key: MapKey,
+}
+
+impl PartialEq for BatchLoad {
+    fn eq(&self, other: &Self) -> bool {
+        self.resolver.grpc_path == other.resolver.grpc_path && self.key == other.key
+    }
+}
+
+impl Eq for BatchLoad {}
+
+impl std::hash::Hash for BatchLoad {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.resolver.grpc_path.hash(state);
+        self.key.hash(state);
+    }
+}
+
+impl GrpcBatchLoader {
+    pub(crate) fn new(client_pool: GrpcClientPool) -> Self {
+        Self { client_pool }
+    }
+}
+
+impl Loader<BatchLoad> for GrpcBatchLoader {
+    type Value = DynamicMessage;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        loads: &[BatchLoad],
+    ) -> std::result::Result<HashMap<BatchLoad, DynamicMessage>, async_graphql::Error> {
+        let mut batches: HashMap<&str, (&NestedResolver, Vec<MapKey>)> = HashMap::new();
+        for load in loads {
+            batches
+                .entry(load.resolver.grpc_path.as_str())
+                .or_insert_with(|| (&load.resolver, Vec::new()))
+                .1
+                .push(load.key.clone());
+        }
+
+        let calls = batches.into_values().map(|(resolver, keys)| async move {
+            let items = resolver.load_batch(&self.client_pool, keys).await?;
+            Ok::<_, async_graphql::Error>(items.into_iter().map(|(key, item)| {
+                let load = BatchLoad {
+                    resolver: resolver.clone(),
+                    key,
+                };
+                (load, item)
+            }))
+        });
+        let mut found = HashMap::new();
+        for items in futures::future::try_join_all(calls).await? {
+            found.extend(items);
+        }
+        Ok(found)
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct GrpcCacheKey {
+    service: String,
+    path: String,
+    request: Vec<u8>,
+}
+
+impl GrpcCacheKey {
+    pub fn new(
+        service: &str,
+        path: &str,
+        request: &DynamicMessage,
+    ) -> std::result::Result<Self, prost::EncodeError> {
+        Ok(Self {
+            service: service.to_string(),
+            path: path.to_string(),
+            request: request.encode_to_vec(),
+        })
+    }
+}
+
+impl TypeRegistry {
+    fn new(
+        pool: &DescriptorPool,
+        client_pool: GrpcClientPool,
+        maps: MapRepresentation,
+        descriptions: bool,
+        naming: Arc<dyn NamingStrategy>,
+        input_type_suffix: String,
+    ) -> Self {
+        let type_names = RESERVED_TYPE_NAMES
+            .iter()
+            .map(|name| (name.to_string(), "a built-in type".to_string()))
+            .collect();
+        Self {
+            objects: BTreeMap::new(),
+            input_objects: BTreeMap::new(),
+            enums: BTreeMap::new(),
+            client_pool,
+            resolvers: HashMap::new(),
+            maps,
+            descriptions,
+            scalars: HashSet::new(),
+            naming,
+            input_type_suffix,
+            type_names,
+            locations: pool
+                .files()
+                .map(|file| {
+                    let locations = file
+                        .file_descriptor_proto()
+                        .source_code_info
+                        .iter()
+                        .flat_map(|info| &info.location)
+                        .map(|location| (location.path.clone(), location.clone()))
+                        .collect();
+                    (file.name().to_string(), locations)
+                })
+                .collect(),
+            batched: false,
+            resolver_services: HashSet::new(),
+        }
+    }
+
+    /// Record that `name` is generated for `origin`, failing if it already names
+    /// something else.
+    fn claim_type_name(&mut self, name: &str, origin: String) -> Result<()> {
+        match self.type_names.get(name) {
+            Some(existing) if *existing == origin => Ok(()),
+            Some(existing) => Err(Error::Schema(format!(
+                "GraphQL type name {name} is generated for both {existing} and {origin}; \
+                 adjust the naming strategy or input type suffix"
+            ))),
+            None => {
+                self.type_names.insert(name.to_string(), origin);
+                Ok(())
+            }
+        }
+    }
+
+    fn register_resolver(
+        &mut self,
+        method: &MethodDescriptor,
+        schema_opts: &GraphqlSchema,
+    ) -> Result<()> {
+        let path = format!("{}/{}", method.parent_service().full_name(), method.name());
+        if !schema_opts.name.is_empty() {
+            if let Some((existing, _)) = self.resolvers.get(&schema_opts.name) {
+                return Err(Error::Schema(format!(
+                    "resolver name {} is used by both {} and {}",
+                    schema_opts.name,
+                    existing.full_name(),
+                    method.full_name()
+                )));
+            }
+            self.resolvers.insert(
+                schema_opts.name.clone(),
+                (method.clone(), schema_opts.clone()),
+            );
+        }
+        self.resolvers
+            .insert(path, (method.clone(), schema_opts.clone()));
+        Ok(())
+    }
+
+    fn nested_resolver(&self, field: &FieldDescriptor, target: &str) -> Result<NestedResolver> {
+        if let Some((method, schema_opts)) = self.resolvers.get(target) {
+            return NestedResolver::new(field, method, Some(schema_opts), self.maps);
+        }
+
+        let (service_name, method_name) = target.split_once('/').ok_or_else(|| {
+            Error::Schema(format!(
+                "unknown resolver {target} on {}: expected a RESOLVER name or \"package.Service/Method\"",
+                field.full_name()
+            ))
+        })?;
+        let method = field
+            .parent_pool()
+            .get_service_by_name(service_name)
+            .and_then(|service| service.methods().find(|m| m.name() == method_name))
+            .ok_or_else(|| {
+                Error::Schema(format!(
+                    "resolver method {target} for {} not found",
+                    field.full_name()
+                ))
+            })?;
+        NestedResolver::new(field, &method, None, self.maps)
+    }
+
+    fn type_name_for_message(&self, desc: &MessageDescriptor) -> String {
+        self.naming.type_name(desc.full_name(), desc.package_name())
+    }
+
+    fn type_name_for_enum(&self, desc: &EnumDescriptor) -> String {
+        self.naming.type_name(desc.full_name(), desc.package_name())
+    }
+
+    /// Leading proto comment for the descriptor at `path`, if descriptions are enabled.
+    fn describe(&self, file: &FileDescriptor, path: &[i32]) -> Option<String> {
+        if !self.descriptions {
+            return None;
+        }
+        self.source_location(file, path)?
+            .leading_comments
+            .as_deref()
+            .and_then(clean_comment)
+    }
+
+    fn source_location(&self, file: &FileDescriptor, path: &[i32]) -> Option<&Location> {
+        self.locations.get(file.name())?.get(path)
+    }
+
+    /// Resolve the `@deprecated` reason: an explicit reason wins, then the trailing proto
+    /// comment, then the GraphQL default. `None` when the element is not deprecated.
+    fn deprecation_reason(
+        &self,
+        deprecated: bool,
+        explicit: &str,
+        file: &FileDescriptor,
+        path: &[i32],
+    ) -> Option<String> {
+        if !explicit.is_empty() {
+            return Some(explicit.to_string());
+        }
+        if !deprecated {
+            return None;
+        }
+        Some(
+            self.source_location(file, path)
+                .and_then(|location| location.trailing_comments.as_deref())
+                .and_then(clean_comment)
+                .unwrap_or_else(|| "No longer supported".to_string()),
+        )
+    }
+
+    /// Deprecation reason for a field marked `[deprecated = true]` or carrying
+    /// `graphql.field.deprecation_reason`.
+    fn field_deprecation(
+        &self,
+        field: &FieldDescriptor,
+        build_ctx: &BuildContext,
+    ) -> Option<String> {
+        let deprecated = field
+            .field_descriptor_proto()
+            .options
+            .as_ref()
+            .is_some_and(|opts| opts.deprecated());
+        let reason = decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
+            .ok()
+            .flatten()
+            .map(|f| f.deprecation_reason)
+            .unwrap_or_default();
+        self.deprecation_reason(deprecated, &reason, &field.parent_file(), field.path())
+    }
+
+    fn ensure_enum(&mut self, desc: &EnumDescriptor) -> Result<TypeRef> {
+        let name = self.type_name_for_enum(desc);
+        self.claim_type_name(&name, format!("enum {}", desc.full_name()))?;
+        if !self.enums.contains_key(&name) {
+            let mut en = Enum::new(name.clone());
+            if let Some(description) = self.describe(&desc.parent_file(), desc.path()) {
+                en = en.description(description);
+            }
+            for value in desc.values() {
+                let mut item = EnumItem::new(self.naming.enum_value_name(&value));
+                if let Some(description) = self.describe(&value.parent_file(), value.path()) {
+                    item = item.description(description);
+                }
+                let deprecated = value
+                    .enum_value_descriptor_proto()
+                    .options
+                    .as_ref()
+                    .is_some_and(|opts| opts.deprecated());
+                if let Some(reason) =
+                    self.deprecation_reason(deprecated, "", &value.parent_file(), value.path())
+                {
+                    item = item.deprecation(Some(&reason));
+                }
+                en = en.item(item);
+            }
+            self.enums.insert(name.clone(), en);
+        }
+        Ok(TypeRef::named(name))
+    }
+
+    fn ensure_input_object(
+        &mut self,
+        message: &MessageDescriptor,
+        build_ctx: &BuildContext,
+    ) -> Result<TypeRef> {
+        let (patch, kind) = match build_ctx.patch {
+            true => ("Patch", "patch input"),
+            false => ("", "input"),
+        };
+        let name = format!(
+            "{}{patch}{}",
+            self.type_name_for_message(message),
+            self.input_type_suffix
+        );
+        self.claim_type_name(&name, format!("{kind} of message {}", message.full_name()))?;
+        if self.input_objects.contains_key(&name) {
+            return Ok(TypeRef::named(name));
+        }
+        // Reserve the name first so recursive fields refer back to it instead of
+        // rebuilding the type; the placeholder is replaced below.
+        self.input_objects
+            .insert(name.clone(), InputObject::new(name.clone()));
+
+        // A message made of a single oneof maps to a `@oneOf` input object, which
+        // requires exactly one nullable member without defaults.
+        let fields: Vec<_> = message
+            .fields()
+            .filter(|field| {
+                let immutable_patch =
+                    build_ctx.patch && has_field_behavior(field, build_ctx, "IMMUTABLE");
+                !field_is_omitted(field, build_ctx)
+                    && !has_field_behavior(field, build_ctx, "OUTPUT_ONLY")
+                    && !immutable_patch
+            })
+            .collect();
+        let oneofs: Option<HashSet<String>> = fields
+            .iter()
+            .map(|field| real_oneof(field).map(|oneof| oneof.full_name().to_string()))
+            .collect();
+        let is_oneof = oneofs.is_some_and(|oneofs| oneofs.len() == 1);
+
+        let mut input = InputObject::new(name.clone());
+        if let Some(description) = self.describe(&message.parent_file(), message.path()) {
+            input = input.description(description);
+        }
+        for field in fields {
+            let field_name = graphql_field_name(&field, build_ctx);
+            let required = !is_oneof && !build_ctx.patch && field_is_required(&field, build_ctx);
+            let ty = self.input_type_for_field(&field, build_ctx, required)?;
+            let mut value = InputValue::new(field_name, ty);
+            if let Some(description) = self.describe(&field.parent_file(), field.path()) {
+                value = value.description(description);
+            }
+            // Only optional input fields may be deprecated.
+            if !required {
+                if let Some(reason) = self.field_deprecation(&field, build_ctx) {
+                    value = value.deprecation(Some(&reason));
+                }
+            }
+            if !is_oneof && !build_ctx.patch {
+                if let Some(default) = field_default(&field, build_ctx)? {
+                    value = value.default_value(default);
+                }
+            }
+            input = input.field(value);
+        }
+        if is_oneof {
+            input = input.oneof();
+        }
+
+        self.input_objects.insert(name.clone(), input);
+        Ok(TypeRef::named(name))
+    }
+
+    fn ensure_object(
+        &mut self,
+        message: &MessageDescriptor,
+        build_ctx: &BuildContext,
+    ) -> Result<TypeRef> {
+        let build_ctx = build_ctx.clone();
+        let name = self.type_name_for_message(message);
+        self.claim_type_name(&name, format!("message {}", message.full_name()))?;
+        if self.objects.contains_key(&name) {
+            return Ok(TypeRef::named(name));
+        }
+        // Reserved up front for recursive messages, like input objects.
+        self.objects.insert(name.clone(), Object::new(name.clone()));
+
+        let mut obj = Object::new(name.clone());
+        if let Some(description) = self.describe(&message.parent_file(), message.path()) {
+            obj = obj.description(description);
+        }
+        for field in message.fields() {
+            if field_is_omitted(&field, &build_ctx)
+                || has_field_behavior(&field, &build_ctx, "INPUT_ONLY")
+            {
+                continue;
+            }
+
+            let field_name = graphql_field_name(&field, &build_ctx);
+            let required = field_is_required(&field, &build_ctx);
+            let ty = self.output_type_for_field(&field, &build_ctx, required)?;
+            let nested = field_resolver(&field, &build_ctx)
+                .map(|target| self.nested_resolver(&field, &target))
+                .transpose()?;
+            if let Some(nested) = &nested {
+                self.batched |= nested.batch.is_some();
+                self.resolver_services.insert(nested.service_name.clone());
+            }
+            let client_pool = self.client_pool.clone();
+            let field_desc = field.clone();
+            let field_name_for_value = field_name.clone();
+            let build_ctx_for_resolver = build_ctx.clone();
+            let maps = self.maps;
+
+            let mut gql_field = Field::new(field_name.clone(), ty, move |ctx| {
+                let build_ctx = build_ctx_for_resolver.clone();
+                let field_desc = field_desc.clone();
+                let field_name_for_value = field_name_for_value.clone();
+                let nested = nested.clone();
+                let client_pool = client_pool.clone();
+                FieldFuture::new(async move {
+                    if let Some(parent) = ctx.parent_value.downcast_ref::<DynamicMessage>() {
+                        if let Some(nested) = nested {
+                            let value = nested
+                                .resolve(&ctx, &client_pool, parent, &field_desc, &build_ctx)
+                                .await?;
+                            return Ok(Some(value));
+                        }
+
+                        // Unset well-known scalars (wrappers in particular) are null.
+                        if is_well_known_field(&field_desc) && !parent.has_field(&field_desc) {
+                            return Ok(Some(FieldValue::NULL));
+                        }
+
+                        let value = parent.get_field(&field_desc);
+                        let field_value =
+                            prost_value_to_field_value(&value, &field_desc, &build_ctx, maps)
+                                .map_err(|e| async_graphql::Error::new(e.to_string()))?;
+                        return Ok(Some(field_value));
+                    }
+
+                    if let Some(GqlValue::Object(map)) = ctx.parent_value.as_value() {
+                        if let Some(val) = map.get(&Name::new(field_name_for_value.clone())) {
+                            return Ok(Some(FieldValue::value(val.clone())));
+                        }
+                    }
+
+                    Ok(Some(FieldValue::NULL))
+                })
+            });
+
+            if let Some(description) = self.describe(&field.parent_file(), field.path()) {
+                gql_field = gql_field.description(description);
+            }
+            if let Some(reason) = self.field_deprecation(&field, &build_ctx) {
+                gql_field = gql_field.deprecation(Some(&reason));
+            }
+
+            // Apply federation field directives
+            if field_is_external(&field, &build_ctx) {
+                gql_field = gql_field.directive(async_graphql::dynamic::Directive::new("external"));
+            }
+            if let Some(requires) = field_requires(&field, &build_ctx) {
                 gql_field = gql_field.directive(
                     async_graphql::dynamic::Directive::new("requires")
                         .argument("fields", GqlValue::String(requires)),
                 );
             }
-            if let Some(provides) = field_provides(&field, &field_ext) {
-                gql_field = gql_field.directive(
-                    async_graphql::dynamic::Directive::new("provides")
-                        .argument("fields", GqlValue::String(provides)),
+            if let Some(provides) = field_provides(&field, &build_ctx) {
+                gql_field = gql_field.directive(
+                    async_graphql::dynamic::Directive::new("provides")
+                        .argument("fields", GqlValue::String(provides)),
+                );
+            }
+            if field_is_shareable(&field, &build_ctx) {
+                gql_field = gql_field.directive(async_graphql::dynamic::Directive::new("shareable"));
+            }
+
+            obj = obj.field(gql_field);
+        }
+
+        for oneof in message.oneofs() {
+            if oneof.fields().any(|field| real_oneof(&field).is_none()) {
+                continue;
+            }
+            obj = obj.field(self.oneof_case_field(&oneof, &build_ctx)?);
+        }
+
+        self.objects.insert(name.clone(), obj);
+        Ok(TypeRef::named(name))
+    }
+
+    /// Build the `<oneof>_case` discriminator telling which member of a oneof is set.
+    fn oneof_case_field(
+        &mut self,
+        oneof: &OneofDescriptor,
+        build_ctx: &BuildContext,
+    ) -> Result<Field> {
+        let field_name = self.naming.oneof_case_field_name(oneof);
+        if oneof
+            .parent_message()
+            .fields()
+            .any(|field| graphql_field_name(&field, build_ctx) == field_name)
+        {
+            return Err(Error::Schema(format!(
+                "{}: field {field_name} collides with the discriminator of oneof {}",
+                oneof.parent_message().full_name(),
+                oneof.name()
+            )));
+        }
+
+        let parent = oneof.parent_message();
+        let enum_name = self.naming.type_name(
+            &format!("{}.{}_case", parent.full_name(), oneof.name()),
+            parent.package_name(),
+        );
+        self.claim_type_name(&enum_name, format!("oneof {}", oneof.full_name()))?;
+        let mut en = Enum::new(enum_name.clone());
+        for member in oneof.fields() {
+            en = en.item(EnumItem::new(oneof_case_name(&member)));
+        }
+        self.enums.insert(enum_name.clone(), en);
+
+        let description = self.describe(&oneof.parent_file(), oneof.path());
+        let oneof = oneof.clone();
+        let field = Field::new(field_name, TypeRef::named(enum_name), move |ctx| {
+            let oneof = oneof.clone();
+            FieldFuture::new(async move {
+                let case = ctx
+                    .parent_value
+                    .downcast_ref::<DynamicMessage>()
+                    .and_then(|parent| oneof.fields().find(|field| parent.has_field(field)));
+                Ok(case.map(|field| GqlValue::Enum(Name::new(oneof_case_name(&field)))))
+            })
+        });
+        Ok(match description {
+            Some(description) => field.description(description),
+            None => field,
+        })
+    }
+
+    /// Build the Relay `XConnection`/`XEdge` pair for the items of a paginated list.
+    fn ensure_connection(
+        &mut self,
+        items: &FieldDescriptor,
+        build_ctx: &BuildContext,
+    ) -> Result<TypeRef> {
+        let Kind::Message(item) = items.kind() else {
+            return Err(Error::Schema(format!(
+                "{}: connection items must be messages",
+                items.full_name()
+            )));
+        };
+        let node_name = self.type_name_for_message(&item);
+        let connection_name = format!("{node_name}Connection");
+        let edge_name = format!("{node_name}Edge");
+        let origin = format!("connection of message {}", item.full_name());
+        self.claim_type_name(&connection_name, origin.clone())?;
+        self.claim_type_name(&edge_name, origin)?;
+        if self.objects.contains_key(&connection_name) {
+            return Ok(TypeRef::named(connection_name));
+        }
+
+        let node_type = self.well_known_type(&item, build_ctx, false)?;
+        let edge = Object::new(edge_name.clone())
+            .field(Field::new(
+                "node",
+                TypeRef::NonNull(Box::new(node_type)),
+                |ctx| {
+                    FieldFuture::new(async move {
+                        let edge = ctx.parent_value.try_downcast_ref::<ConnectionEdge>()?;
+                        Ok(Some(message_to_field_value(&edge.node)?))
+                    })
+                },
+            ))
+            .field(Field::new(
+                "cursor",
+                TypeRef::named_nn(TypeRef::STRING),
+                |ctx| {
+                    FieldFuture::new(async move {
+                        let edge = ctx.parent_value.try_downcast_ref::<ConnectionEdge>()?;
+                        Ok(Some(GqlValue::from(edge.cursor.encode())))
+                    })
+                },
+            ));
+
+        self.claim_type_name(PAGE_INFO_TYPE, "Relay PageInfo".to_string())?;
+        if !self.objects.contains_key(PAGE_INFO_TYPE) {
+            self.objects
+                .insert(PAGE_INFO_TYPE.to_string(), page_info_object());
+        }
+
+        let connection = Object::new(connection_name.clone())
+            .field(Field::new(
+                "edges",
+                TypeRef::named_nn_list_nn(edge_name.clone()),
+                |ctx| {
+                    FieldFuture::new(async move {
+                        let page = ctx.parent_value.try_downcast_ref::<ConnectionPage>()?;
+                        Ok(Some(FieldValue::list(
+                            page.edges.iter().cloned().map(FieldValue::owned_any),
+                        )))
+                    })
+                },
+            ))
+            .field(Field::new(
+                "pageInfo",
+                TypeRef::named_nn(PAGE_INFO_TYPE),
+                |ctx| {
+                    FieldFuture::new(async move {
+                        let page = ctx.parent_value.try_downcast_ref::<ConnectionPage>()?;
+                        Ok(Some(FieldValue::owned_any(page.clone())))
+                    })
+                },
+            ));
+
+        self.objects.insert(edge_name, edge);
+        self.objects.insert(connection_name.clone(), connection);
+        Ok(TypeRef::named(connection_name))
+    }
+
+    fn scalar(&mut self, name: &'static str) -> TypeRef {
+        self.scalars.insert(name);
+        TypeRef::named(name)
+    }
+
+    /// GraphQL type for a well-known message, see [`WellKnownType`].
+    fn well_known_type(
+        &mut self,
+        message: &MessageDescriptor,
+        build_ctx: &BuildContext,
+        is_input: bool,
+    ) -> Result<TypeRef> {
+        Ok(match WellKnownType::of(message) {
+            Some(WellKnownType::Timestamp) => self.scalar(DATETIME_SCALAR),
+            Some(WellKnownType::Duration) => TypeRef::named(TypeRef::STRING),
+            Some(WellKnownType::Wrapper) => {
+                let value = message.get_field_by_name("value").ok_or_else(|| {
+                    Error::Schema(format!("{} has no value", message.full_name()))
+                })?;
+                if value.kind() == Kind::Bytes {
+                    // BytesValue is base64 in both directions, like its JSON form.
+                    TypeRef::named(TypeRef::STRING)
+                } else {
+                    type_for_field(self, &value, build_ctx, false, is_input)?
+                }
+            }
+            Some(WellKnownType::Json) => self.scalar(JSON_SCALAR),
+            Some(WellKnownType::Empty) => TypeRef::named(TypeRef::BOOLEAN),
+            None if is_input => self.ensure_input_object(message, build_ctx)?,
+            None => self.ensure_object(message, build_ctx)?,
+        })
+    }
+
+    fn input_type_for_field(
+        &mut self,
+        field: &FieldDescriptor,
+        build_ctx: &BuildContext,
+        required: bool,
+    ) -> Result<TypeRef> {
+        type_for_field(self, field, build_ctx, required, true)
+    }
+
+    fn output_type_for_field(
+        &mut self,
+        field: &FieldDescriptor,
+        build_ctx: &BuildContext,
+        required: bool,
+    ) -> Result<TypeRef> {
+        type_for_field(self, field, build_ctx, required, false)
+    }
+}
+
+fn type_for_field(
+    registry: &mut TypeRegistry,
+    field: &FieldDescriptor,
+    build_ctx: &BuildContext,
+    required: bool,
+    is_input: bool,
+) -> Result<TypeRef> {
+    if field.is_map() && registry.maps == MapRepresentation::Json {
+        let base = registry.scalar(JSON_SCALAR);
+        return Ok(if required {
+            TypeRef::NonNull(Box::new(base))
+        } else {
+            base
+        });
+    }
+
+    let base = match field.kind() {
+        Kind::Bool => TypeRef::named(TypeRef::BOOLEAN),
+        Kind::String => TypeRef::named(TypeRef::STRING),
+        Kind::Bytes => {
+            if is_input {
+                TypeRef::named(TypeRef::UPLOAD)
+            } else {
+                TypeRef::named(TypeRef::STRING)
+            }
+        }
+        Kind::Float | Kind::Double => TypeRef::named(TypeRef::FLOAT),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 | Kind::Uint32 | Kind::Fixed32 => {
+            TypeRef::named(TypeRef::INT)
+        }
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => registry.scalar(INT64_SCALAR),
+        Kind::Uint64 | Kind::Fixed64 => registry.scalar(UINT64_SCALAR),
+        Kind::Enum(en) => registry.ensure_enum(&en)?,
+        Kind::Message(msg) if WellKnownType::of(&msg).is_some() => {
+            registry.well_known_type(&msg, build_ctx, is_input)?
+        }
+        Kind::Message(msg) => {
+            if is_input {
+                registry.ensure_input_object(&msg, build_ctx)?
+            } else {
+                registry.ensure_object(&msg, build_ctx)?
+            }
+        }
+    };
+
+    if field.is_map() {
+        // Entries are never null; the list itself follows `required`.
+        let list = TypeRef::List(Box::new(TypeRef::NonNull(Box::new(base))));
+        return Ok(if required {
+            TypeRef::NonNull(Box::new(list))
+        } else {
+            list
+        });
+    }
+
+    let base = if required {
+        TypeRef::NonNull(Box::new(base))
+    } else {
+        base
+    };
+
+    if field.is_list() {
+        Ok(TypeRef::List(Box::new(base)))
+    } else {
+        Ok(base)
+    }
+}
+
+fn placeholder_query_root() -> Object {
+    let ty = TypeRef::NonNull(Box::new(TypeRef::named(TypeRef::BOOLEAN)));
+    let field = Field::new("__placeholder", ty, |_| {
+        FieldFuture::new(async { Ok(Some(GqlValue::Boolean(true))) })
+    });
+    Object::new("Query").field(field)
+}
+
+#[derive(Clone)]
+struct ArgumentSpec {
+    name: String,
+    ty: TypeRef,
+    default: Option<GqlValue>,
+    description: Option<String>,
+    deprecation: Option<String>,
+}
+
+impl ArgumentSpec {
+    fn into_input_value(self) -> InputValue {
+        let mut input = InputValue::new(self.name, self.ty);
+        if let Some(description) = self.description {
+            input = input.description(description);
+        }
+        if let Some(reason) = self.deprecation.as_deref() {
+            input = input.deprecation(Some(reason));
+        }
+        match self.default {
+            Some(default) => input.default_value(default),
+            None => input,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct OperationConfig {
+    service_name: String,
+    grpc_path: String,
+    input_desc: MessageDescriptor,
+    output_desc: MessageDescriptor,
+    request_wrapper_name: Option<String>,
+    bindings: Vec<ArgumentBinding>,
+    /// Client-streaming methods take a list argument and stream one request per item.
+    client_streaming: bool,
+    connection: Option<ConnectionPlan>,
+    field_mask: Option<FieldMaskPlan>,
+    update_mask: Option<UpdateMaskPlan>,
+    return_type: TypeRef,
+    args: Vec<ArgumentSpec>,
+    description: Option<String>,
+    deprecation: Option<String>,
+    schema_opts: GraphqlSchema,
+    build_ctx: BuildContext,
+    maps: MapRepresentation,
+}
+
+impl OperationConfig {
+    fn new(
+        service: &prost_reflect::ServiceDescriptor,
+        method: &prost_reflect::MethodDescriptor,
+        schema_opts: &GraphqlSchema,
+        build_ctx: BuildContext,
+        registry: &mut TypeRegistry,
+    ) -> Result<Self> {
+        let input_desc = method.input();
+        let output_desc = method.output();
+
+        let return_required = schema_opts
+            .response
+            .as_ref()
+            .map(|resp| resp.required)
+            .unwrap_or(false);
+
+        let connection = match schema_opts.response.as_ref().filter(|resp| resp.connection) {
+            Some(response) => {
+                if method.is_client_streaming() || method.is_server_streaming() {
+                    return Err(Error::Schema(format!(
+                        "{}: connections are only supported on unary methods",
+                        method.full_name()
+                    )));
+                }
+                Some(ConnectionPlan::new(method, response)?)
+            }
+            None => None,
+        };
+
+        let return_type = compute_return_type(
+            &output_desc,
+            schema_opts.response.as_ref(),
+            connection.as_ref(),
+            &build_ctx,
+            registry,
+            return_required,
+        )?;
+
+        let mut request_wrapper_name = schema_opts
+            .request
+            .as_ref()
+            .filter(|req| !req.name.is_empty())
+            .map(|req| req.name.clone());
+        let plucks = schema_opts
+            .request
+            .as_ref()
+            .map(|req| req.plucks.as_slice())
+            .unwrap_or_default();
+
+        if request_wrapper_name.is_some() && !plucks.is_empty() {
+            return Err(Error::Schema(format!(
+                "{}: request.name and request.plucks cannot be combined",
+                method.full_name()
+            )));
+        }
+
+        // A client stream is a list of either whole request messages (under
+        // `request.name`, default `input`) or values of a single plucked field.
+        let client_streaming = method.is_client_streaming();
+        if client_streaming {
+            if plucks.len() > 1 {
+                return Err(Error::Schema(format!(
+                    "{}: client-streaming methods take at most one request.plucks entry",
+                    method.full_name()
+                )));
+            }
+            if plucks.is_empty() && request_wrapper_name.is_none() {
+                request_wrapper_name = Some("input".to_string());
+            }
+        }
+
+        let mut bindings = argument_bindings(&input_desc, plucks, &build_ctx)?;
+        if let Some(plan) = &connection {
+            // `first`/`after` take the place of the page fields.
+            bindings.retain(|binding| {
+                let field = binding.field();
+                binding.path.len() > 1 || (*field != plan.page_size && *field != plan.page_token)
+            });
+        }
+        let field_mask = match schema_opts.request.as_ref().filter(|req| req.field_mask) {
+            Some(_) => Some(FieldMaskPlan::new(
+                method,
+                schema_opts,
+                connection.as_ref(),
+                &build_ctx,
+                registry,
+            )?),
+            None => None,
+        };
+        let update_mask = match schema_opts.request.as_ref().filter(|req| req.update_mask) {
+            Some(_) => Some(UpdateMaskPlan::new(method)?),
+            None => None,
+        };
+        // Masks filled by the gateway are not arguments.
+        let mask_fields: Vec<_> = field_mask
+            .iter()
+            .map(|plan| &plan.mask_field)
+            .chain(update_mask.iter().map(|plan| &plan.mask_field))
+            .collect();
+        bindings.retain(|binding| !mask_fields.contains(&&binding.path[0]));
+        // Patches cannot change IMMUTABLE fields, so they are not offered as arguments either.
+        if update_mask.is_some() {
+            bindings.retain(|binding| {
+                !binding
+                    .path
+                    .iter()
+                    .any(|field| has_field_behavior(field, &build_ctx, "IMMUTABLE"))
+            });
+        }
+        // The arguments of update_mask methods, and the requests built from them, are patches.
+        let build_ctx = BuildContext {
+            patch: update_mask.is_some(),
+            ..build_ctx
+        };
+        let mut args = build_arguments(
+            &input_desc,
+            &request_wrapper_name,
+            &bindings,
+            &build_ctx,
+            registry,
+        )?;
+        if client_streaming {
+            for arg in &mut args {
+                let item = match &arg.ty {
+                    TypeRef::NonNull(_) => arg.ty.clone(),
+                    ty => TypeRef::NonNull(Box::new(ty.clone())),
+                };
+                arg.ty = TypeRef::NonNull(Box::new(TypeRef::List(Box::new(item))));
+                arg.default = None;
+                arg.deprecation = None;
+            }
+        }
+        if connection.is_some() {
+            for (name, ty) in [("first", TypeRef::INT), ("after", TypeRef::STRING)] {
+                if args.iter().any(|arg| arg.name == name) {
+                    return Err(Error::Schema(format!(
+                        "{}: argument {name} collides with the connection arguments",
+                        method.full_name()
+                    )));
+                }
+                args.push(ArgumentSpec {
+                    name: name.to_string(),
+                    ty: TypeRef::named(ty),
+                    default: None,
+                    description: None,
+                    deprecation: None,
+                });
+            }
+        }
+        let grpc_path = format!("/{}/{}", service.full_name(), method.name());
+        let service_name = service.full_name().to_string();
+        let description = registry.describe(&method.parent_file(), method.path());
+        let deprecated = method
+            .method_descriptor_proto()
+            .options
+            .as_ref()
+            .is_some_and(|opts| opts.deprecated());
+        let deprecation = registry.deprecation_reason(
+            deprecated,
+            &schema_opts.deprecation_reason,
+            &method.parent_file(),
+            method.path(),
+        );
+
+        Ok(Self {
+            service_name,
+            grpc_path,
+            input_desc,
+            output_desc,
+            request_wrapper_name,
+            bindings,
+            client_streaming,
+            connection,
+            field_mask,
+            update_mask,
+            return_type,
+            args,
+            description,
+            deprecation,
+            schema_opts: schema_opts.clone(),
+            build_ctx,
+            maps: registry.maps,
+        })
+    }
+}
+
+fn build_field(
+    name: String,
+    service: &prost_reflect::ServiceDescriptor,
+    method: &prost_reflect::MethodDescriptor,
+    schema_opts: &GraphqlSchema,
+    build_ctx: BuildContext,
+    registry: &mut TypeRegistry,
+    client_pool: GrpcClientPool,
+) -> Result<Field> {
+    if method.is_server_streaming() {
+        return Err(Error::Schema(format!(
+            "{}: server-streaming methods can only be exposed as a SUBSCRIPTION",
+            method.full_name()
+        )));
+    }
+    let config = OperationConfig::new(service, method, schema_opts, build_ctx, registry)?;
+    let args = config.args.clone();
+    let description = config.description.clone();
+    let deprecation = config.deprecation.clone();
+
+    let field = Field::new(name, config.return_type.clone(), move |ctx| {
+        let client_pool = client_pool.clone();
+        let config = config.clone();
+        FieldFuture::new(async move {
+            let response = if config.client_streaming {
+                let mut requests = build_request_stream(
+                    &config.input_desc,
+                    &ctx,
+                    &config.request_wrapper_name,
+                    &config.bindings,
+                    config.update_mask.as_ref(),
+                    &config.build_ctx,
+                )?;
+                if let Some(plan) = &config.field_mask {
+                    for request in &mut requests {
+                        plan.apply(&ctx, request, &config.build_ctx);
+                    }
+                }
+                client_streaming_call(
+                    &client_pool,
+                    &config.service_name,
+                    &config.grpc_path,
+                    &config.output_desc,
+                    requests,
+                )
+                .await?
+            } else {
+                let mut request_msg = build_request_message(
+                    &config.input_desc,
+                    &ctx,
+                    &config.request_wrapper_name,
+                    &config.bindings,
+                    &config.build_ctx,
+                )?;
+                if let Some(plan) = &config.field_mask {
+                    plan.apply(&ctx, &mut request_msg, &config.build_ctx);
+                }
+                if let Some(plan) = &config.update_mask {
+                    let paths = sent_input_paths(
+                        &ctx,
+                        &config.input_desc,
+                        &config.request_wrapper_name,
+                        &config.bindings,
+                        &config.build_ctx,
+                    );
+                    plan.apply(&mut request_msg, paths, &config.build_ctx)?;
+                }
+                if let Some(plan) = &config.connection {
+                    let (start, first) = plan.apply_arguments(&ctx, &mut request_msg)?;
+                    let response = unary_call(
+                        &ctx,
+                        &client_pool,
+                        &config.service_name,
+                        &config.grpc_path,
+                        &config.output_desc,
+                        request_msg,
+                    )
+                    .await?;
+                    return Ok(Some(FieldValue::owned_any(
+                        plan.page(&response, start, first),
+                    )));
+                }
+                unary_call(
+                    &ctx,
+                    &client_pool,
+                    &config.service_name,
+                    &config.grpc_path,
+                    &config.output_desc,
+                    request_msg,
+                )
+                .await?
+            };
+
+            let value = apply_response_pluck(
+                &response,
+                &config.schema_opts,
+                &config.build_ctx,
+                config.maps,
+            )?;
+            Ok(Some(value))
+        })
+    });
+
+    let mut field = field;
+    if let Some(description) = description {
+        field = field.description(description);
+    }
+    if let Some(reason) = deprecation.as_deref() {
+        field = field.deprecation(Some(reason));
+    }
+    for arg in args {
+        field = field.argument(arg.into_input_value());
+    }
+
+    Ok(field)
+}
+
+/// Issue a unary gRPC call, memoized per request through [`GrpcResponseCache`].
+async fn unary_call(
+    ctx: &ResolverContext<'_>,
+    client_pool: &GrpcClientPool,
+    service_name: &str,
+    grpc_path: &str,
+    output_desc: &MessageDescriptor,
+    request_msg: DynamicMessage,
+) -> async_graphql::Result<DynamicMessage> {
+    let cache_key = GrpcCacheKey::new(service_name, grpc_path, &request_msg)
+        .map_err(|e| async_graphql::Error::new(format!("failed to encode request: {e}")))?;
+
+    if let Some(cache) = ctx.data_opt::<GrpcResponseCache>() {
+        if let Some(response) = cache.get_message(&cache_key) {
+            return Ok(response);
+        }
+    }
+
+    let response = uncached_unary_call(
+        client_pool,
+        service_name,
+        grpc_path,
+        output_desc,
+        request_msg,
+    )
+    .await?;
+
+    if let Some(cache) = ctx.data_opt::<GrpcResponseCache>() {
+        cache.insert_message(cache_key, response.clone());
+    }
+    Ok(response)
+}
+
+/// Issue a unary gRPC call.
+async fn uncached_unary_call(
+    client_pool: &GrpcClientPool,
+    service_name: &str,
+    grpc_path: &str,
+    output_desc: &MessageDescriptor,
+    request_msg: DynamicMessage,
+) -> async_graphql::Result<DynamicMessage> {
+    let client = client_pool.get(service_name).ok_or_else(|| {
+        async_graphql::Error::new(format!("gRPC client {service_name} not found"))
+    })?;
+
+    let mut grpc = Grpc::new(client.channel());
+
+    grpc.ready()
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("gRPC not ready: {e}")))?;
+
+    let codec = ReflectCodec::new(output_desc.clone());
+    let path: http::uri::PathAndQuery = grpc_path
+        .parse()
+        .map_err(|e| async_graphql::Error::new(format!("invalid gRPC path: {e}")))?;
+
+    let response = grpc
+        .unary(tonic::Request::new(request_msg), path, codec)
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("gRPC error: {e}")))?
+        .into_inner();
+    Ok(response)
+}
+
+/// Issue a client-streaming gRPC call, sending `requests` in order.
+async fn client_streaming_call(
+    client_pool: &GrpcClientPool,
+    service_name: &str,
+    grpc_path: &str,
+    output_desc: &MessageDescriptor,
+    requests: Vec<DynamicMessage>,
+) -> async_graphql::Result<DynamicMessage> {
+    let client = client_pool.get(service_name).ok_or_else(|| {
+        async_graphql::Error::new(format!("gRPC client {service_name} not found"))
+    })?;
+    let mut grpc = Grpc::new(client.channel());
+
+    grpc.ready()
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("gRPC not ready: {e}")))?;
+
+    let codec = ReflectCodec::new(output_desc.clone());
+    let path: http::uri::PathAndQuery = grpc_path
+        .parse()
+        .map_err(|e| async_graphql::Error::new(format!("invalid gRPC path: {e}")))?;
+
+    let response = grpc
+        .client_streaming(
+            tonic::Request::new(tokio_stream::iter(requests)),
+            path,
+            codec,
+        )
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("gRPC error: {e}")))?
+        .into_inner();
+    Ok(response)
+}
+
+/// Relay pagination over an AIP-style list method (`GraphqlResponse.connection`).
+///
+/// `first`/`after` become `page_size`/`page_token`. Page tokens only address whole pages,
+/// so a cursor is a page token plus the number of items of that page to skip; the last
+/// item of a page points at the next page instead.
+#[derive(Clone)]
+struct ConnectionPlan {
+    page_size: FieldDescriptor,
+    page_token: FieldDescriptor,
+    next_page_token: FieldDescriptor,
+    items: FieldDescriptor,
+}
+
+impl ConnectionPlan {
+    fn new(method: &MethodDescriptor, response: &GraphqlResponse) -> Result<Self> {
+        let singular = |message: &MessageDescriptor, name: &str, kind: Kind| {
+            message
+                .get_field_by_name(name)
+                .filter(|field| field.kind() == kind && !field.is_list())
+                .ok_or_else(|| {
+                    Error::Schema(format!(
+                        "{}: connections need a singular {kind:?} field {name} on {}",
+                        method.full_name(),
+                        message.full_name()
+                    ))
+                })
+        };
+        let input = method.input();
+        let output = method.output();
+
+        let items = if response.pluck.is_empty() {
+            let mut repeated = output.fields().filter(|field| field.is_list());
+            match (repeated.next(), repeated.next()) {
+                (Some(items), None) => Some(items),
+                _ => None,
+            }
+        } else {
+            output
+                .get_field_by_name(&response.pluck)
+                .filter(FieldDescriptor::is_list)
+        }
+        .ok_or_else(|| {
+            Error::Schema(format!(
+                "{}: connections need a single repeated field on {} or a response.pluck naming one",
+                method.full_name(),
+                output.full_name()
+            ))
+        })?;
+
+        Ok(Self {
+            page_size: singular(&input, "page_size", Kind::Int32)?,
+            page_token: singular(&input, "page_token", Kind::String)?,
+            next_page_token: singular(&output, "next_page_token", Kind::String)?,
+            items,
+        })
+    }
+
+    /// Translate `first`/`after` onto the request, returning where the page starts and
+    /// how many items to return.
+    fn apply_arguments(
+        &self,
+        ctx: &ResolverContext<'_>,
+        request: &mut DynamicMessage,
+    ) -> async_graphql::Result<(Cursor, Option<usize>)> {
+        let first = match ctx.args.get("first").filter(|value| !value.is_null()) {
+            Some(value) => Some(
+                usize::try_from(value.i64()?)
+                    .map_err(|_| async_graphql::Error::new("first must not be negative"))?,
+            ),
+            None => None,
+        };
+        let start = match ctx.args.get("after").filter(|value| !value.is_null()) {
+            Some(value) => Cursor::decode(value.string()?)?,
+            None => Cursor::default(),
+        };
+
+        request.set_field(&self.page_token, Value::String(start.token.clone()));
+        if let Some(first) = first {
+            // Fetch the skipped items too, so `first` items remain after the cursor.
+            let size = first
+                .checked_add(start.offset)
+                .and_then(|size| i32::try_from(size).ok())
+                .ok_or_else(|| async_graphql::Error::new("first is too large"))?;
+            request.set_field(&self.page_size, Value::I32(size));
+        }
+        Ok((start, first))
+    }
+
+    fn page(
+        &self,
+        response: &DynamicMessage,
+        start: Cursor,
+        first: Option<usize>,
+    ) -> ConnectionPage {
+        let items = match response.get_field(&self.items).as_ref() {
+            Value::List(items) => items.clone(),
+            _ => Vec::new(),
+        };
+        let next_token = response
+            .get_field(&self.next_page_token)
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let end = first
+            .and_then(|first| start.offset.checked_add(first))
+            .map_or(items.len(), |end| end.min(items.len()));
+        let skip = start.offset.min(end);
+        let edges = items[skip..end]
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| {
+                let position = skip + idx + 1;
+                let cursor = if position == items.len() && !next_token.is_empty() {
+                    Cursor {
+                        token: next_token.clone(),
+                        offset: 0,
+                    }
+                } else {
+                    Cursor {
+                        token: start.token.clone(),
+                        offset: position,
+                    }
+                };
+                item.as_message().map(|node| ConnectionEdge {
+                    node: node.clone(),
+                    cursor,
+                })
+            })
+            .collect();
+
+        ConnectionPage {
+            edges,
+            has_next_page: end < items.len() || !next_token.is_empty(),
+            has_previous_page: skip > 0 || !start.token.is_empty(),
+        }
+    }
+}
+
+/// Position in a paginated list: a page token and the items of that page to skip.
+#[derive(Clone, Default)]
+struct Cursor {
+    token: String,
+    offset: usize,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        BASE64.encode(format!("{}:{}", self.offset, self.token))
+    }
+
+    fn decode(cursor: &str) -> async_graphql::Result<Self> {
+        let invalid = || async_graphql::Error::new(format!("invalid cursor {cursor:?}"));
+        let raw = BASE64.decode(cursor).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let (offset, token) = raw.split_once(':').ok_or_else(invalid)?;
+        // Offsets index into a single page, whose size is an int32.
+        let offset = offset
+            .parse::<i32>()
+            .ok()
+            .and_then(|offset| usize::try_from(offset).ok())
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            token: token.to_string(),
+            offset,
+        })
+    }
+}
+
+/// Resolved value of an `XConnection` object, also used for its `pageInfo`.
+#[derive(Clone)]
+struct ConnectionPage {
+    edges: Vec<ConnectionEdge>,
+    has_next_page: bool,
+    has_previous_page: bool,
+}
+
+#[derive(Clone)]
+struct ConnectionEdge {
+    node: DynamicMessage,
+    cursor: Cursor,
+}
+
+/// The Relay `PageInfo` type, resolved from a [`ConnectionPage`].
+fn page_info_object() -> Object {
+    let cursor = |name: &str, pick: fn(&ConnectionPage) -> Option<&ConnectionEdge>| {
+        Field::new(name, TypeRef::named(TypeRef::STRING), move |ctx| {
+            FieldFuture::new(async move {
+                let page = ctx.parent_value.try_downcast_ref::<ConnectionPage>()?;
+                Ok(pick(page).map(|edge| GqlValue::from(edge.cursor.encode())))
+            })
+        })
+    };
+    let flag = |name: &str, pick: fn(&ConnectionPage) -> bool| {
+        Field::new(name, TypeRef::named_nn(TypeRef::BOOLEAN), move |ctx| {
+            FieldFuture::new(async move {
+                let page = ctx.parent_value.try_downcast_ref::<ConnectionPage>()?;
+                Ok(Some(GqlValue::from(pick(page))))
+            })
+        })
+    };
+    Object::new(PAGE_INFO_TYPE)
+        .field(flag("hasNextPage", |page| page.has_next_page))
+        .field(flag("hasPreviousPage", |page| page.has_previous_page))
+        .field(cursor("startCursor", |page| page.edges.first()))
+        .field(cursor("endCursor", |page| page.edges.last()))
+}
+
+#[cfg(test)]
+mod connection_tests {
+    use super::tests::{fixture_schema, spawn_fixtures, type_fields};
+    use super::*;
+
+    #[tokio::test]
+    async fn paginated_lists_map_to_relay_connections() {
+        let schema = fixture_schema(&["operations.Operations"], &spawn_fixtures().await);
+
+        assert_eq!(
+            type_fields(&schema, "Query").await["listBooks"],
+            "listBooks(author: String, first: Int, after: String): operations_BookConnection"
+        );
+
+        let page = |args: &str| {
+            let query = format!(
+                r#"{{ listBooks(author: "Ada", {args}) {{
+                    edges {{ cursor node {{ title }} }}
+                    pageInfo {{ hasNextPage hasPreviousPage startCursor endCursor }}
+                }} }}"#
+            );
+            let schema = schema.clone();
+            async move {
+                let response = schema.execute(async_graphql::Request::new(query)).await;
+                assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+                response.data.into_json().expect("valid JSON response")["listBooks"].clone()
+            }
+        };
+        let titles = |page: &serde_json::Value| -> Vec<String> {
+            page["edges"]
+                .as_array()
+                .expect("edges")
+                .iter()
+                .map(|edge| edge["node"]["title"].as_str().expect("title").to_string())
+                .collect()
+        };
+
+        let first = page("first: 3").await;
+        assert_eq!(titles(&first), ["Ada #0", "Ada #1", "Ada #2"]);
+        assert_eq!(first["pageInfo"]["hasNextPage"], true);
+        assert_eq!(first["pageInfo"]["hasPreviousPage"], false);
+
+        let end = first["pageInfo"]["endCursor"].as_str().expect("end cursor");
+        let rest = page(&format!("first: 3, after: \"{end}\"")).await;
+        assert_eq!(titles(&rest), ["Ada #3", "Ada #4"]);
+        assert_eq!(rest["pageInfo"]["hasNextPage"], false);
+        assert_eq!(rest["pageInfo"]["hasPreviousPage"], true);
+
+        // Cursors inside a page resume right after their edge.
+        let middle = first["edges"][0]["cursor"].as_str().expect("cursor");
+        let resumed = page(&format!("first: 1, after: \"{middle}\"")).await;
+        assert_eq!(titles(&resumed), ["Ada #1"]);
+        assert_eq!(resumed["pageInfo"]["hasNextPage"], true);
+
+        let invalid = schema
+            .execute(async_graphql::Request::new(
+                r#"{ listBooks(after: "not a cursor") { edges { cursor } } }"#,
+            ))
+            .await;
+        assert!(invalid.errors[0].message.contains("invalid cursor"));
+
+        // Offsets beyond a page are rejected instead of overflowing `first + offset`.
+        for offset in [usize::MAX.to_string(), (i32::MAX as u64 + 1).to_string()] {
+            let cursor = BASE64.encode(format!("{offset}:tok"));
+            let oversized = schema
+                .execute(async_graphql::Request::new(format!(
+                    r#"{{ listBooks(first: 1, after: "{cursor}") {{ edges {{ cursor }} }} }}"#
+                )))
+                .await;
+            assert!(
+                oversized.errors[0].message.contains("invalid cursor"),
+                "{:?}",
+                oversized.errors
+            );
+        }
+    }
+}
+
+/// Fills the request `FieldMask` from the GraphQL selection set (`request.field_mask`).
+///
+/// Selected fields are translated back to proto paths, descending into singular message
+/// fields. Repeated fields, maps and well-known types end a path, as FieldMask only allows
+/// them in the last position. For connections the paths are relative to the node message.
+#[derive(Clone)]
+struct FieldMaskPlan {
+    /// The `read_mask`/`field_mask` request field.
+    mask_field: FieldDescriptor,
+    /// Message the selection set is read against.
+    root: MessageDescriptor,
+    /// Path from the response message to `root`, e.g. a plucked field.
+    prefix: Option<String>,
+    /// The plucked response value is no message to select from; the mask is just `prefix`.
+    fixed: bool,
+    connection: bool,
+    /// Fields that nested resolvers read from their parent, keyed by resolved field.
+    resolver_reads: Arc<HashMap<String, Vec<String>>>,
+}
+
+impl FieldMaskPlan {
+    fn new(
+        method: &MethodDescriptor,
+        schema_opts: &GraphqlSchema,
+        connection: Option<&ConnectionPlan>,
+        build_ctx: &BuildContext,
+        registry: &TypeRegistry,
+    ) -> Result<Self> {
+        let input = method.input();
+        let mask_field = field_mask_field(&input, &["read_mask", "field_mask"]).ok_or_else(|| {
+            Error::Schema(format!(
+                "{}: request.field_mask needs a google.protobuf.FieldMask field read_mask or field_mask on {}",
+                method.full_name(),
+                input.full_name()
+            ))
+        })?;
+
+        let pluck = schema_opts
+            .response
+            .as_ref()
+            .filter(|resp| !resp.pluck.is_empty())
+            .and_then(|resp| method.output().get_field_by_name(&resp.pluck));
+        let (root, prefix, fixed) = match (connection, pluck) {
+            (Some(plan), _) => match plan.items.kind() {
+                Kind::Message(node) => (node, None, false),
+                _ => (method.output(), None, true),
+            },
+            (None, Some(pluck)) => match maskable_message(&pluck) {
+                Some(msg) => (msg, Some(pluck.name().to_string()), false),
+                None => (method.output(), Some(pluck.name().to_string()), true),
+            },
+            (None, None) => (method.output(), None, false),
+        };
+
+        let mut resolver_reads = HashMap::new();
+        collect_resolver_reads(
+            &root,
+            build_ctx,
+            registry,
+            &mut HashSet::new(),
+            &mut resolver_reads,
+        )?;
+
+        Ok(Self {
+            mask_field,
+            root,
+            prefix,
+            fixed,
+            connection: connection.is_some(),
+            resolver_reads: Arc::new(resolver_reads),
+        })
+    }
+
+    fn apply(
+        &self,
+        ctx: &ResolverContext<'_>,
+        request: &mut DynamicMessage,
+        build_ctx: &BuildContext,
+    ) {
+        let mut paths = BTreeSet::new();
+        if self.fixed {
+            paths.extend(self.prefix.clone());
+        } else {
+            for field in ctx.look_ahead().selection_fields() {
+                let mut selection: Vec<_> = field.selection_set().collect();
+                if self.connection {
+                    selection = selection
+                        .into_iter()
+                        .filter(|edges| edges.name() == "edges")
+                        .flat_map(|edges| edges.selection_set().collect::<Vec<_>>())
+                        .filter(|node| node.name() == "node")
+                        .flat_map(|node| node.selection_set().collect::<Vec<_>>())
+                        .collect();
+                }
+                self.collect(
+                    &self.root,
+                    selection,
+                    self.prefix.as_deref(),
+                    build_ctx,
+                    &mut paths,
+                );
+            }
+        }
+
+        set_field_mask(request, &self.mask_field, paths);
+    }
+
+    fn collect<'a>(
+        &self,
+        desc: &MessageDescriptor,
+        selection: impl IntoIterator<Item = SelectionField<'a>>,
+        prefix: Option<&str>,
+        build_ctx: &BuildContext,
+        paths: &mut BTreeSet<String>,
+    ) {
+        let path = |name: &str| match prefix {
+            Some(prefix) => format!("{prefix}.{name}"),
+            None => name.to_string(),
+        };
+        for selected in selection {
+            let name = selected.name();
+            // The oneof case discriminator needs whichever member is set.
+            if let Some(oneof) = desc
+                .oneofs()
+                .find(|oneof| build_ctx.naming.oneof_case_field_name(oneof) == name)
+            {
+                paths.extend(oneof.fields().map(|member| path(member.name())));
+                continue;
+            }
+            let Some(field) = desc.fields().find(|field| {
+                !field_is_omitted(field, build_ctx) && graphql_field_name(field, build_ctx) == name
+            }) else {
+                continue;
+            };
+
+            let field_path = path(field.name());
+            if let Some(reads) = self.resolver_reads.get(field.full_name()) {
+                paths.extend(
+                    reads
+                        .iter()
+                        .filter(|read| desc.get_field_by_name(read).is_some())
+                        .map(|read| path(read)),
                 );
+                paths.insert(field_path);
+                continue;
             }
-            if field_is_shareable(&field, &field_ext) {
-                gql_field = gql_field.directive(async_graphql::dynamic::Directive::new("shareable"));
+            match maskable_message(&field) {
+                Some(child) => {
+                    let before = paths.len();
+                    self.collect(
+                        &child,
+                        selected.selection_set(),
+                        Some(&field_path),
+                        build_ctx,
+                        paths,
+                    );
+                    // Only `__typename` was selected; keep the field itself.
+                    if paths.len() == before {
+                        paths.insert(field_path);
+                    }
+                }
+                None => {
+                    paths.insert(field_path);
+                }
             }
+        }
+    }
+}
 
-            obj = obj.field(gql_field);
+#[cfg(test)]
+mod field_mask_tests {
+    use super::tests::{fixture_schema, spawn_fixtures, type_fields};
+
+    #[tokio::test]
+    async fn field_masks_follow_the_selection_set() {
+        let schema = fixture_schema(&["operations.Operations"], &spawn_fixtures().await);
+        assert_eq!(
+            type_fields(&schema, "Query").await["profile"],
+            "profile(user_id: String): operations_Profile",
+            "read_mask is not an argument"
+        );
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{
+                    profile(user_id: "ada") {
+                        bio
+                        contact_case
+                        user { __typename }
+                        greeting: last_greeting { ...Greeting }
+                    }
+                }
+                fragment Greeting on operations_Greeting {
+                    message
+                    meta { from { displayName } }
+                }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["profile"]["bio"],
+            "bio,email,last_greeting.message,last_greeting.meta.from,phone,user"
+        );
+        assert_eq!(
+            data["profile"]["greeting"]["meta"]["from"]["displayName"],
+            "User ada"
+        );
+    }
+}
+
+/// Fills the request `update_mask` with the input fields the client sent
+/// (`request.update_mask`).
+///
+/// Explicit `null`s are included, since they clear the field. Input objects for singular
+/// message fields are descended into, so `{ profile: { bio: "…" } }` yields `bio` rather
+/// than replacing the whole profile. Requests that send no field are rejected, as an empty
+/// mask asks the backend to replace the whole resource. Patch inputs leave `IMMUTABLE`
+/// fields out; requests that still set one are rejected.
+#[derive(Clone)]
+struct UpdateMaskPlan {
+    mask_field: FieldDescriptor,
+    /// The only message field of the request besides the mask; paths are relative to it.
+    resource: Option<FieldDescriptor>,
+}
+
+impl UpdateMaskPlan {
+    fn new(method: &MethodDescriptor) -> Result<Self> {
+        let input = method.input();
+        let mask_field = field_mask_field(&input, &["update_mask"]).ok_or_else(|| {
+            Error::Schema(format!(
+                "{}: request.update_mask needs a google.protobuf.FieldMask field update_mask on {}",
+                method.full_name(),
+                input.full_name()
+            ))
+        })?;
+        let mut messages = input
+            .fields()
+            .filter(|field| *field != mask_field && maskable_message(field).is_some());
+        let resource = match (messages.next(), messages.next()) {
+            (Some(resource), None) => Some(resource),
+            _ => None,
+        };
+        Ok(Self {
+            mask_field,
+            resource,
+        })
+    }
+
+    /// Set the mask of `request` from the request-relative `paths` the client sent.
+    fn apply(
+        &self,
+        request: &mut DynamicMessage,
+        paths: BTreeSet<String>,
+        build_ctx: &BuildContext,
+    ) -> async_graphql::Result<()> {
+        reject_immutable_paths(&request.descriptor(), &paths, build_ctx)?;
+        let paths: BTreeSet<String> = match &self.resource {
+            Some(resource) => {
+                let prefix = format!("{}.", resource.name());
+                paths
+                    .into_iter()
+                    .filter_map(|path| path.strip_prefix(&prefix).map(str::to_string))
+                    .collect()
+            }
+            None => paths,
+        };
+        if paths.is_empty() {
+            let target = match &self.resource {
+                Some(resource) => resource.full_name().to_string(),
+                None => request.descriptor().full_name().to_string(),
+            };
+            return Err(async_graphql::Error::new(format!(
+                "no fields to update in {target}"
+            )));
+        }
+        set_field_mask(request, &self.mask_field, paths);
+        Ok(())
+    }
+}
+
+/// The request-relative proto paths of the input fields the client sent.
+fn sent_input_paths(
+    ctx: &ResolverContext<'_>,
+    input_desc: &MessageDescriptor,
+    wrapper: &Option<String>,
+    bindings: &[ArgumentBinding],
+    build_ctx: &BuildContext,
+) -> BTreeSet<String> {
+    let mut paths = BTreeSet::new();
+    match wrapper.as_ref().and_then(|name| ctx.args.get(name)) {
+        Some(value) => {
+            if let GqlValue::Object(values) = value.as_value() {
+                input_field_paths(input_desc, values, None, build_ctx, &mut paths);
+            }
+        }
+        None => {
+            for binding in bindings {
+                if let Some(value) = ctx.args.get(&binding.name) {
+                    input_field_path(
+                        binding.field(),
+                        value.as_value(),
+                        binding.proto_path(),
+                        build_ctx,
+                        &mut paths,
+                    );
+                }
+            }
         }
-
-        self.objects.insert(name.clone(), obj);
-        TypeRef::named(name)
     }
+    paths
+}
 
-    fn input_type_for_field(
-        &mut self,
-        field: &FieldDescriptor,
-        field_ext: &ExtensionDescriptor,
-        required: bool,
-    ) -> TypeRef {
-        type_for_field(self, field, field_ext, required, true)
+/// Fail with the first sent path that sets or descends into an `IMMUTABLE` field.
+fn reject_immutable_paths(
+    input: &MessageDescriptor,
+    paths: &BTreeSet<String>,
+    build_ctx: &BuildContext,
+) -> async_graphql::Result<()> {
+    for path in paths {
+        let mut desc = Some(input.clone());
+        for segment in path.split('.') {
+            let Some(field) = desc.and_then(|desc| desc.get_field_by_name(segment)) else {
+                break;
+            };
+            if has_field_behavior(&field, build_ctx, "IMMUTABLE") {
+                return Err(async_graphql::Error::new(format!(
+                    "{} is immutable and cannot be updated",
+                    field.full_name()
+                )));
+            }
+            desc = maskable_message(&field);
+        }
     }
+    Ok(())
+}
 
-    fn output_type_for_field(
-        &mut self,
-        field: &FieldDescriptor,
-        field_ext: &ExtensionDescriptor,
-        required: bool,
-    ) -> TypeRef {
-        type_for_field(self, field, field_ext, required, false)
+/// Collect the proto paths of the fields present in an input object.
+fn input_field_paths(
+    desc: &MessageDescriptor,
+    values: &IndexMap<Name, GqlValue>,
+    prefix: Option<&str>,
+    build_ctx: &BuildContext,
+    paths: &mut BTreeSet<String>,
+) {
+    for field in desc.fields() {
+        if field_is_omitted(&field, build_ctx) {
+            continue;
+        }
+        if let Some(value) = values.get(&Name::new(graphql_field_name(&field, build_ctx))) {
+            let path = match prefix {
+                Some(prefix) => format!("{prefix}.{}", field.name()),
+                None => field.name().to_string(),
+            };
+            input_field_path(&field, value, path, build_ctx, paths);
+        }
     }
 }
 
-fn type_for_field(
-    registry: &mut TypeRegistry,
+fn input_field_path(
     field: &FieldDescriptor,
-    field_ext: &ExtensionDescriptor,
-    required: bool,
-    is_input: bool,
-) -> TypeRef {
-    let base = match field.kind() {
-        Kind::Bool => TypeRef::named(TypeRef::BOOLEAN),
-        Kind::String => TypeRef::named(TypeRef::STRING),
-        Kind::Bytes => {
-            if is_input {
-                TypeRef::named(TypeRef::UPLOAD)
-            } else {
-                TypeRef::named(TypeRef::STRING)
-            }
-        }
-        Kind::Float | Kind::Double => TypeRef::named(TypeRef::FLOAT),
-        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 | Kind::Uint32 | Kind::Fixed32 => {
-            TypeRef::named(TypeRef::INT)
+    value: &GqlValue,
+    path: String,
+    build_ctx: &BuildContext,
+    paths: &mut BTreeSet<String>,
+) {
+    match (maskable_message(field), value) {
+        (Some(child), GqlValue::Object(values)) if !values.is_empty() => {
+            input_field_paths(&child, values, Some(&path), build_ctx, paths)
         }
-        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 | Kind::Uint64 | Kind::Fixed64 => {
-            TypeRef::named(TypeRef::STRING)
+        _ => {
+            paths.insert(path);
         }
-        Kind::Enum(en) => registry.ensure_enum(&en),
-        Kind::Message(msg) => {
-            if is_input {
-                registry.ensure_input_object(&msg, field_ext)
-            } else {
-                registry.ensure_object(&msg, field_ext)
+    }
+}
+
+/// The singular `google.protobuf.FieldMask` field of `desc` with the first matching name.
+fn field_mask_field(desc: &MessageDescriptor, names: &[&str]) -> Option<FieldDescriptor> {
+    names
+        .iter()
+        .filter_map(|name| desc.get_field_by_name(name))
+        .find(|field| match field.kind() {
+            Kind::Message(msg) => {
+                !field.is_list() && msg.full_name() == "google.protobuf.FieldMask"
             }
-        }
-    };
+            _ => false,
+        })
+}
 
-    let base = if required {
-        TypeRef::NonNull(Box::new(base))
-    } else {
-        base
+fn set_field_mask(
+    request: &mut DynamicMessage,
+    mask_field: &FieldDescriptor,
+    paths: BTreeSet<String>,
+) {
+    let Kind::Message(mask_desc) = mask_field.kind() else {
+        return;
     };
-
-    if field.is_list() {
-        TypeRef::List(Box::new(base))
-    } else {
-        base
+    let mut mask = DynamicMessage::new(mask_desc.clone());
+    if let Some(paths_field) = mask_desc.get_field_by_name("paths") {
+        mask.set_field(
+            &paths_field,
+            Value::List(paths.into_iter().map(Value::String).collect()),
+        );
     }
+    request.set_field(mask_field, Value::Message(mask));
 }
 
-fn placeholder_query_root() -> Object {
-    let ty = TypeRef::NonNull(Box::new(TypeRef::named(TypeRef::BOOLEAN)));
-    let field = Field::new("__placeholder", ty, |_| {
-        FieldFuture::new(async { Ok(Some(GqlValue::Boolean(true))) })
-    });
-    Object::new("Query").field(field)
+/// The message a field mask path may descend into: singular, non-map, non-well-known.
+fn maskable_message(field: &FieldDescriptor) -> Option<MessageDescriptor> {
+    match field.kind() {
+        Kind::Message(msg)
+            if !field.is_list() && !field.is_map() && WellKnownType::of(&msg).is_none() =>
+        {
+            Some(msg)
+        }
+        _ => None,
+    }
 }
 
-#[derive(Clone)]
-struct ArgumentSpec {
-    name: String,
-    ty: TypeRef,
+#[cfg(test)]
+mod update_mask_tests {
+    use super::tests::{fixture_schema, spawn_fixtures, type_fields};
+
+    #[tokio::test]
+    async fn update_masks_list_sent_fields_and_explicit_nulls() {
+        let schema = fixture_schema(&["operations.Operations"], &spawn_fixtures().await);
+        assert_eq!(
+            type_fields(&schema, "Mutation").await["updateProfile"],
+            "updateProfile(profile: operations_ProfilePatchInput): operations_Profile",
+            "update_mask is not an argument"
+        );
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"mutation {
+                    updateProfile(profile: {
+                        bio: "ignored"
+                        user: null
+                        email: "ada@example.com"
+                        last_greeting: { message: "Hi", meta: {} }
+                    }) {
+                        bio
+                        email
+                        last_greeting { message }
+                    }
+                }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["updateProfile"],
+            serde_json::json!({
+                "bio": "bio,email,last_greeting.message,last_greeting.meta,user",
+                "email": "ada@example.com",
+                "last_greeting": { "message": "Hi" },
+            })
+        );
+
+        // An empty mask would replace the whole profile, so nothing is sent.
+        for query in [
+            "mutation { updateProfile(profile: {}) { bio } }",
+            "mutation { updateProfile(profile: null) { bio } }",
+            "mutation { updateProfile { bio } }",
+        ] {
+            let response = schema.execute(async_graphql::Request::new(query)).await;
+            assert_eq!(
+                response.errors.first().map(|e| e.message.as_str()),
+                Some("no fields to update in operations.UpdateProfileRequest.profile"),
+                "{query}"
+            );
+        }
+    }
 }
 
-impl ArgumentSpec {
-    fn into_input_value(self) -> InputValue {
-        InputValue::new(self.name, self.ty)
+/// Record the parent fields read by the nested resolvers reachable from `desc` through
+/// singular message fields.
+fn collect_resolver_reads(
+    desc: &MessageDescriptor,
+    build_ctx: &BuildContext,
+    registry: &TypeRegistry,
+    visited: &mut HashSet<String>,
+    reads: &mut HashMap<String, Vec<String>>,
+) -> Result<()> {
+    if !visited.insert(desc.full_name().to_string()) {
+        return Ok(());
+    }
+    for field in desc.fields() {
+        if let Some(target) = field_resolver(&field, build_ctx) {
+            let resolver = registry.nested_resolver(&field, &target)?;
+            reads.insert(field.full_name().to_string(), resolver.parent_reads());
+        } else if let Some(child) = maskable_message(&field) {
+            collect_resolver_reads(&child, build_ctx, registry, visited, reads)?;
+        }
     }
+    Ok(())
 }
 
+/// Nested field resolution configured through `graphql.field.resolver`.
+///
+/// The target is a unary method, referenced either by the name of a RESOLVER method or
+/// as `package.Service/Method`. Its request is built
+/// from the parent message: each request field is copied from the same-named field of
+/// the resolved field's current value (when it is a message) or of the parent itself.
 #[derive(Clone)]
-struct OperationConfig {
+struct NestedResolver {
     service_name: String,
     grpc_path: String,
     input_desc: MessageDescriptor,
     output_desc: MessageDescriptor,
-    request_wrapper_name: Option<String>,
-    return_type: TypeRef,
-    args: Vec<ArgumentSpec>,
-    schema_opts: GraphqlSchema,
-    field_ext: ExtensionDescriptor,
+    /// Response field holding the value; `None` when the whole response is the value.
+    response_field: Option<FieldDescriptor>,
+    batch: Option<BatchPlan>,
+    maps: MapRepresentation,
 }
 
-impl OperationConfig {
+/// Batched form of a nested resolver (`response.batch_key`).
+///
+/// The target request consists of a single repeated key field (e.g. `repeated string ids`)
+/// and the response carries a repeated list of the resolved type. Items are matched back to
+/// their parents through the singular key field named by `batch_key` (`id`).
+#[derive(Clone)]
+struct BatchPlan {
+    /// Repeated request field receiving the collected keys.
+    key_field: FieldDescriptor,
+    /// Repeated response field holding the resolved items.
+    items_field: FieldDescriptor,
+    /// Key field on each item, also read from the parent to build the key.
+    item_key_field: FieldDescriptor,
+}
+
+impl BatchPlan {
+    /// Validate the batch configuration of `method` for `field`; `None` without a
+    /// `batch_key`.
     fn new(
-        service: &prost_reflect::ServiceDescriptor,
-        method: &prost_reflect::MethodDescriptor,
-        schema_opts: &GraphqlSchema,
-        field_ext: ExtensionDescriptor,
-        registry: &mut TypeRegistry,
-    ) -> Result<Self> {
+        field: &FieldDescriptor,
+        method: &MethodDescriptor,
+        pluck: Option<&str>,
+        batch_key: &str,
+    ) -> Result<Option<Self>> {
+        if batch_key.is_empty() {
+            return Ok(None);
+        }
+        let invalid = |reason: String| {
+            Error::Schema(format!(
+                "resolver {} with batch_key {batch_key}: {reason}",
+                method.full_name()
+            ))
+        };
+
+        let target = match field.kind() {
+            Kind::Message(target) if !field.is_list() => target,
+            _ => {
+                return Err(invalid(format!(
+                    "{} is not a singular message field",
+                    field.full_name()
+                )))
+            }
+        };
+
         let input_desc = method.input();
+        let mut request_fields = input_desc.fields();
+        let key_field = match (request_fields.next(), request_fields.next()) {
+            (Some(key_field), None)
+                if key_field.is_list()
+                    && !key_field.is_map()
+                    && is_batch_key_kind(&key_field.kind()) =>
+            {
+                key_field
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "request {} must consist of a single repeated scalar field",
+                    input_desc.full_name()
+                )))
+            }
+        };
+
+        let item_key_field = target
+            .get_field_by_name(batch_key)
+            .filter(|f| !f.is_list() && f.kind() == key_field.kind())
+            .ok_or_else(|| {
+                invalid(format!(
+                    "{} has no singular field {batch_key} of the type of {}",
+                    target.full_name(),
+                    key_field.full_name()
+                ))
+            })?;
+
         let output_desc = method.output();
+        let items_field = match pluck {
+            Some(pluck) => output_desc.get_field_by_name(pluck),
+            None => {
+                let mut lists = output_desc
+                    .fields()
+                    .filter(|f| f.is_list() && f.kind() == field.kind());
+                match (lists.next(), lists.next()) {
+                    (Some(items_field), None) => Some(items_field),
+                    _ => None,
+                }
+            }
+        }
+        .filter(|f| f.is_list() && !f.is_map() && f.kind() == field.kind())
+        .ok_or_else(|| {
+            invalid(format!(
+                "response {} has no single repeated {} field to match items from",
+                output_desc.full_name(),
+                target.full_name()
+            ))
+        })?;
 
-        let return_required = schema_opts
-            .response
-            .as_ref()
-            .map(|resp| resp.required)
-            .unwrap_or(false);
+        Ok(Some(Self {
+            key_field,
+            items_field,
+            item_key_field,
+        }))
+    }
+}
 
-        let return_type = compute_return_type(
-            &output_desc,
-            schema_opts.response.as_ref(),
-            &field_ext,
-            registry,
-            return_required,
+fn is_batch_key_kind(kind: &Kind) -> bool {
+    matches!(
+        kind,
+        Kind::Bool
+            | Kind::String
+            | Kind::Int32
+            | Kind::Sint32
+            | Kind::Sfixed32
+            | Kind::Uint32
+            | Kind::Fixed32
+            | Kind::Int64
+            | Kind::Sint64
+            | Kind::Sfixed64
+            | Kind::Uint64
+            | Kind::Fixed64
+    )
+}
+
+#[cfg(test)]
+mod batching_tests {
+    use super::tests::{
+        fixture_builder, operations, spawn_backend, TestOperations, FIXTURES_DESCRIPTOR,
+    };
+    use super::*;
+    use std::sync::atomic::Ordering;
+
+    #[tokio::test]
+    async fn nested_resolvers_are_batched_per_request() {
+        let backend = TestOperations::default();
+        let calls = backend.user_calls.clone();
+        let pool = spawn_backend(
+            tonic::transport::Server::builder().add_service(
+                operations::operations_server::OperationsServer::new(backend),
+            ),
+            &["operations.Operations"],
+        )
+        .await;
+        let schema = fixture_builder(&["operations.Operations"])
+            .with_batch_delay(Duration::from_millis(100))
+            .build(&pool)
+            .expect("schema builds");
+
+        // Root fields resolve concurrently, so both lists' keys land in the same batch.
+        let request = || {
+            async_graphql::Request::new(
+                r#"{ reviews(author_ids: ["a", "b", "c", "a"]) { author { id displayName } } more: reviews(author_ids: ["d"]) { author { id } } }"#,
+            )
+        };
+        let response = schema.execute(request()).await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        let names: Vec<_> = data["reviews"]
+            .as_array()
+            .expect("reviews list")
+            .iter()
+            .map(|review| review["author"]["displayName"].clone())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec!["User a", "User b", "User c", "User a"]
+                .into_iter()
+                .map(serde_json::Value::from)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            1,
+            "expected a single batch call"
         );
 
-        let request_wrapper_name = schema_opts
-            .request
-            .as_ref()
-            .filter(|req| !req.name.is_empty())
-            .map(|req| req.name.clone());
+        // Requests run by the executor directly, as over WebSockets, are batched too.
+        let response = schema.executor().execute(request()).await;
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            2,
+            "expected one more batch call"
+        );
+    }
 
-        let args = build_arguments(&input_desc, &request_wrapper_name, &field_ext, registry);
-        let grpc_path = format!("/{}/{}", service.full_name(), method.name());
-        let service_name = service.full_name().to_string();
+    #[test]
+    fn batch_keys_are_validated() {
+        let pool = DescriptorPool::decode(FIXTURES_DESCRIPTOR).expect("descriptor decodes");
+        let author = pool
+            .get_message_by_name("operations.Review")
+            .and_then(|review| review.get_field_by_name("author"))
+            .expect("author field");
+        let method = |name: &str| {
+            pool.get_service_by_name("operations.Operations")
+                .and_then(|service| service.methods().find(|m| m.name() == name))
+                .expect("method exists")
+        };
+        let batch_users = method("BatchGetUsers");
+
+        let plan = BatchPlan::new(&author, &batch_users, Some("users"), "id")
+            .expect("valid batch_key")
+            .expect("batched");
+        assert_eq!(plan.key_field.name(), "ids");
+        assert_eq!(plan.items_field.name(), "users");
+        assert!(BatchPlan::new(&author, &batch_users, Some("users"), "")
+            .expect("no batch_key")
+            .is_none());
+
+        let error = |method, pluck, batch_key| {
+            BatchPlan::new(&author, &method, pluck, batch_key)
+                .err()
+                .expect("invalid batch configuration")
+                .to_string()
+        };
+        // `trusted` is a bool, the request keys are strings.
+        assert!(error(batch_users.clone(), Some("users"), "trusted").contains("no singular field"));
+        assert!(error(batch_users, Some("missing"), "id").contains("no single repeated"));
+        // GetUserRequest has a singular `id`, not a repeated key field.
+        assert!(error(method("GetUser"), None, "id").contains("single repeated scalar field"));
+    }
+}
+
+impl NestedResolver {
+    fn new(
+        field: &FieldDescriptor,
+        method: &MethodDescriptor,
+        schema_opts: Option<&GraphqlSchema>,
+        maps: MapRepresentation,
+    ) -> Result<Self> {
+        let service = method.parent_service();
+        if method.is_client_streaming() || method.is_server_streaming() {
+            return Err(Error::Schema(format!(
+                "resolver {} for {} must be a unary method",
+                method.full_name(),
+                field.full_name()
+            )));
+        }
+
+        let input_desc = method.input();
+        let output_desc = method.output();
+        let response = schema_opts.and_then(|opts| opts.response.as_ref());
+        let pluck = response
+            .filter(|resp| !resp.pluck.is_empty())
+            .map(|resp| resp.pluck.as_str());
+        let batch_key = response
+            .map(|resp| resp.batch_key.as_str())
+            .unwrap_or_default();
+
+        let batch = BatchPlan::new(field, method, pluck, batch_key)?;
+        let response_field = if batch.is_some() {
+            None
+        } else {
+            Self::response_field(field, method, &output_desc, pluck)?
+        };
 
         Ok(Self {
-            service_name,
-            grpc_path,
+            service_name: service.full_name().to_string(),
+            grpc_path: format!("/{}/{}", service.full_name(), method.name()),
             input_desc,
             output_desc,
-            request_wrapper_name,
-            return_type,
-            args,
-            schema_opts: schema_opts.clone(),
-            field_ext,
+            response_field,
+            batch,
+            maps,
         })
     }
-}
 
-fn build_field(
-    name: String,
-    service: &prost_reflect::ServiceDescriptor,
-    method: &prost_reflect::MethodDescriptor,
-    schema_opts: &GraphqlSchema,
-    field_ext: ExtensionDescriptor,
-    registry: &mut TypeRegistry,
-    client_pool: GrpcClientPool,
-) -> Result<Field> {
-    let config = OperationConfig::new(service, method, schema_opts, field_ext, registry)?;
-    let args = config.args.clone();
+    fn response_field(
+        field: &FieldDescriptor,
+        method: &MethodDescriptor,
+        output_desc: &MessageDescriptor,
+        pluck: Option<&str>,
+    ) -> Result<Option<FieldDescriptor>> {
+        Ok(match pluck {
+            Some(pluck) => Some(output_desc.get_field_by_name(pluck).ok_or_else(|| {
+                Error::Schema(format!(
+                    "resolver {} plucks unknown field {pluck}",
+                    method.full_name()
+                ))
+            })?),
+            None => resolver_response_field(field, output_desc).ok_or_else(|| {
+                Error::Schema(format!(
+                    "resolver {} response {} has no field matching {}",
+                    method.full_name(),
+                    output_desc.full_name(),
+                    field.full_name()
+                ))
+            })?,
+        })
+    }
 
-    let field = Field::new(name, config.return_type.clone(), move |ctx| {
-        let client_pool = client_pool.clone();
-        let config = config.clone();
-        FieldFuture::new(async move {
-            let client = client_pool.get(&config.service_name).ok_or_else(|| {
-                async_graphql::Error::new(format!("gRPC client {} not found", config.service_name))
-            })?;
+    /// Names of the fields read from the parent (or the resolved field's current value)
+    /// to build the request.
+    fn parent_reads(&self) -> Vec<String> {
+        match &self.batch {
+            Some(batch) => vec![batch.item_key_field.name().to_string()],
+            None => self
+                .input_desc
+                .fields()
+                .map(|field| field.name().to_string())
+                .collect(),
+        }
+    }
 
-            let request_msg = build_request_message(
-                &config.input_desc,
-                &ctx,
-                &config.request_wrapper_name,
-                &config.field_ext,
-            )?;
-            let cache_key =
-                GrpcCacheKey::new(&config.service_name, &config.grpc_path, &request_msg).map_err(
-                    |e| async_graphql::Error::new(format!("failed to encode request: {e}")),
-                )?;
+    /// Read the batch key for this parent from the same sources as [`Self::build_request`].
+    fn batch_key(
+        &self,
+        batch: &BatchPlan,
+        parent: &DynamicMessage,
+        field: &FieldDescriptor,
+    ) -> Option<MapKey> {
+        nested_source_value(parent, field, &batch.item_key_field)?.into_map_key()
+    }
 
-            if let Some(cache) = ctx.data_opt::<GrpcResponseCache>() {
-                if let Some(val) = cache.get(&cache_key) {
-                    return Ok(Some(val));
+    /// Send one batch RPC for `keys` and index the returned items by their key.
+    ///
+    /// Batches bypass [`GrpcResponseCache`]: its entries are keyed by the encoded request,
+    /// and a batch request carries whichever keys were collected in the same tick, so the
+    /// same request is rarely sent twice. The data loader already sends each key only once
+    /// per batch.
+    async fn load_batch(
+        &self,
+        client_pool: &GrpcClientPool,
+        keys: Vec<MapKey>,
+    ) -> async_graphql::Result<HashMap<MapKey, DynamicMessage>> {
+        let Some(batch) = &self.batch else {
+            return Ok(HashMap::new());
+        };
+        let mut request = DynamicMessage::new(self.input_desc.clone());
+        request.set_field(
+            &batch.key_field,
+            Value::List(keys.into_iter().map(Value::from).collect()),
+        );
+
+        let response = uncached_unary_call(
+            client_pool,
+            &self.service_name,
+            &self.grpc_path,
+            &self.output_desc,
+            request,
+        )
+        .await?;
+
+        let mut items = HashMap::new();
+        if let Value::List(list) = response.get_field(&batch.items_field).as_ref() {
+            for item in list {
+                if let Value::Message(msg) = item {
+                    let key = msg.get_field(&batch.item_key_field).into_owned();
+                    if let Some(key) = key.into_map_key() {
+                        items.insert(key, msg.clone());
+                    }
                 }
             }
+        }
+        Ok(items)
+    }
 
-            let mut grpc = Grpc::new(client.channel());
+    fn build_request(&self, parent: &DynamicMessage, field: &FieldDescriptor) -> DynamicMessage {
+        let mut request = DynamicMessage::new(self.input_desc.clone());
+        for request_field in self.input_desc.fields() {
+            if let Some(value) = nested_source_value(parent, field, &request_field) {
+                request.set_field(&request_field, value);
+            }
+        }
+        request
+    }
 
-            grpc.ready()
-                .await
-                .map_err(|e| async_graphql::Error::new(format!("gRPC not ready: {e}")))?;
+    async fn resolve(
+        &self,
+        ctx: &ResolverContext<'_>,
+        client_pool: &GrpcClientPool,
+        parent: &DynamicMessage,
+        field: &FieldDescriptor,
+        build_ctx: &BuildContext,
+    ) -> async_graphql::Result<FieldValue<'static>> {
+        if let Some(batch) = &self.batch {
+            let Some(key) = self.batch_key(batch, parent, field) else {
+                return Ok(FieldValue::NULL);
+            };
+            // Every request gets a data loader from `BatchLoaderExtension`.
+            let loader = ctx.data::<DataLoader<GrpcBatchLoader>>()?;
+            let item = loader
+                .load_one(BatchLoad {
+                    resolver: self.clone(),
+                    key,
+                })
+                .await?;
+            return Ok(item.map(FieldValue::owned_any).unwrap_or(FieldValue::NULL));
+        }
 
-            let codec = ReflectCodec::new(config.output_desc.clone());
-            let path: http::uri::PathAndQuery = config
-                .grpc_path
-                .parse()
-                .map_err(|e| async_graphql::Error::new(format!("invalid gRPC path: {e}")))?;
+        let request = self.build_request(parent, field);
+        let response = unary_call(
+            ctx,
+            client_pool,
+            &self.service_name,
+            &self.grpc_path,
+            &self.output_desc,
+            request,
+        )
+        .await?;
+
+        let value = match &self.response_field {
+            Some(response_field) => prost_value_to_field_value(
+                &response.get_field(response_field),
+                response_field,
+                build_ctx,
+                self.maps,
+            )?,
+            None => message_to_field_value(&response)?,
+        };
+        Ok(value)
+    }
+}
 
-            let response = grpc
-                .unary(tonic::Request::new(request_msg), path, codec)
-                .await
-                .map_err(|e| async_graphql::Error::new(format!("gRPC error: {e}")))?
-                .into_inner();
+/// Find a value for `target` on the resolved field's current message value, falling back
+/// to the parent message. Only set fields with the same name and shape are used.
+fn nested_source_value(
+    parent: &DynamicMessage,
+    field: &FieldDescriptor,
+    target: &FieldDescriptor,
+) -> Option<Value> {
+    let current = match parent.get_field(field).as_ref() {
+        Value::Message(msg) if parent.has_field(field) => Some(msg.clone()),
+        _ => None,
+    };
 
-            let value = apply_response_pluck(&response, &config.schema_opts, &config.field_ext)?;
-            if let Some(cache) = ctx.data_opt::<GrpcResponseCache>() {
-                cache.insert(cache_key, value.clone());
+    current
+        .iter()
+        .chain(std::iter::once(parent))
+        .find_map(|source| {
+            let source_field = source.descriptor().get_field_by_name(target.name())?;
+            if same_field_shape(&source_field, target) && source.has_field(&source_field) {
+                Some(source.get_field(&source_field).into_owned())
+            } else {
+                None
             }
-            Ok(Some(value))
         })
-    });
+}
 
-    let mut field = field;
-    for arg in args {
-        field = field.argument(arg.into_input_value());
+/// Locate the part of a resolver response that carries the value for `field`.
+///
+/// Returns `Some(None)` when the response message itself has the field's type, otherwise
+/// the same-named response field or the first one with a matching type.
+fn resolver_response_field(
+    field: &FieldDescriptor,
+    output_desc: &MessageDescriptor,
+) -> Option<Option<FieldDescriptor>> {
+    if let Kind::Message(msg) = field.kind() {
+        if !field.is_list() && msg == *output_desc {
+            return Some(None);
+        }
     }
 
-    Ok(field)
+    output_desc
+        .get_field_by_name(field.name())
+        .filter(|candidate| same_field_shape(candidate, field))
+        .or_else(|| {
+            output_desc
+                .fields()
+                .find(|candidate| same_field_shape(candidate, field))
+        })
+        .map(Some)
+}
+
+fn same_field_shape(a: &FieldDescriptor, b: &FieldDescriptor) -> bool {
+    a.kind() == b.kind() && a.is_list() == b.is_list() && a.is_map() == b.is_map()
 }
 
 fn build_subscription_field(
@@ -795,12 +4364,20 @@ fn build_subscription_field(
     service: &prost_reflect::ServiceDescriptor,
     method: &prost_reflect::MethodDescriptor,
     schema_opts: &GraphqlSchema,
-    field_ext: ExtensionDescriptor,
+    build_ctx: BuildContext,
     registry: &mut TypeRegistry,
     client_pool: GrpcClientPool,
 ) -> Result<SubscriptionField> {
-    let config = OperationConfig::new(service, method, schema_opts, field_ext, registry)?;
+    let config = OperationConfig::new(service, method, schema_opts, build_ctx, registry)?;
+    if config.connection.is_some() {
+        return Err(Error::Schema(format!(
+            "{}: connections cannot be used on subscriptions",
+            method.full_name()
+        )));
+    }
     let args = config.args.clone();
+    let description = config.description.clone();
+    let deprecation = config.deprecation.clone();
 
     let field = SubscriptionField::new(name, config.return_type.clone(), move |ctx| {
         let client_pool = client_pool.clone();
@@ -810,12 +4387,6 @@ fn build_subscription_field(
                 async_graphql::Error::new(format!("gRPC client {} not found", config.service_name))
             })?;
 
-            let request_msg = build_request_message(
-                &config.input_desc,
-                &ctx,
-                &config.request_wrapper_name,
-                &config.field_ext,
-            )?;
             let mut grpc = Grpc::new(client.channel());
 
             grpc.ready()
@@ -827,17 +4398,60 @@ fn build_subscription_field(
                 .parse()
                 .map_err(|e| async_graphql::Error::new(format!("invalid gRPC path: {e}")))?;
 
-            let response = grpc
-                .server_streaming(tonic::Request::new(request_msg), path, codec)
+            // Bidi methods stream the list argument up while responses stream down.
+            let response = if config.client_streaming {
+                let mut requests = build_request_stream(
+                    &config.input_desc,
+                    &ctx,
+                    &config.request_wrapper_name,
+                    &config.bindings,
+                    config.update_mask.as_ref(),
+                    &config.build_ctx,
+                )?;
+                if let Some(plan) = &config.field_mask {
+                    for request in &mut requests {
+                        plan.apply(&ctx, request, &config.build_ctx);
+                    }
+                }
+                grpc.streaming(
+                    tonic::Request::new(tokio_stream::iter(requests)),
+                    path,
+                    codec,
+                )
                 .await
-                .map_err(|e| async_graphql::Error::new(format!("gRPC error: {e}")))?;
+            } else {
+                let mut request_msg = build_request_message(
+                    &config.input_desc,
+                    &ctx,
+                    &config.request_wrapper_name,
+                    &config.bindings,
+                    &config.build_ctx,
+                )?;
+                if let Some(plan) = &config.field_mask {
+                    plan.apply(&ctx, &mut request_msg, &config.build_ctx);
+                }
+                if let Some(plan) = &config.update_mask {
+                    let paths = sent_input_paths(
+                        &ctx,
+                        &config.input_desc,
+                        &config.request_wrapper_name,
+                        &config.bindings,
+                        &config.build_ctx,
+                    );
+                    plan.apply(&mut request_msg, paths, &config.build_ctx)?;
+                }
+                grpc.server_streaming(tonic::Request::new(request_msg), path, codec)
+                    .await
+            }
+            .map_err(|e| async_graphql::Error::new(format!("gRPC error: {e}")))?;
 
             let schema_opts = config.schema_opts.clone();
-            let field_ext = config.field_ext.clone();
+            let build_ctx = config.build_ctx.clone();
+            let maps = config.maps;
             let stream = response.into_inner().map(move |item| {
                 let msg =
                     item.map_err(|e| async_graphql::Error::new(format!("gRPC error: {e}")))?;
-                apply_response_pluck(&msg, &schema_opts, &field_ext)
+                apply_response_pluck(&msg, &schema_opts, &build_ctx, maps)
                     .map_err(|e| async_graphql::Error::new(e.to_string()))
             });
 
@@ -846,6 +4460,12 @@ fn build_subscription_field(
     });
 
     let mut field = field;
+    if let Some(description) = description {
+        field = field.description(description);
+    }
+    if let Some(reason) = deprecation.as_deref() {
+        field = field.deprecation(Some(reason));
+    }
     for arg in args {
         field = field.argument(arg.into_input_value());
     }
@@ -853,51 +4473,432 @@ fn build_subscription_field(
     Ok(field)
 }
 
+#[cfg(test)]
+mod streaming_tests {
+    use super::tests::{fixture_schema, spawn_fixtures, type_fields};
+    use super::*;
+
+    #[tokio::test]
+    async fn client_and_bidi_streams_send_list_items() {
+        let schema = fixture_schema(&["operations.Operations"], &spawn_fixtures().await);
+
+        let mutation = type_fields(&schema, "Mutation").await;
+        assert_eq!(
+            mutation["collectHellos"],
+            "collectHellos(input: [operations_HelloRequestInput!]!): [operations_HelloReply]"
+        );
+        assert_eq!(
+            mutation["uploadChunks"],
+            "uploadChunks(data: [Upload!]!): operations_UploadReply!"
+        );
+        assert_eq!(
+            type_fields(&schema, "Subscription").await["chat"],
+            "chat(name: [String!]!): operations_HelloReply"
+        );
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"mutation { collectHellos(input: [{ name: "Ada" }, {}]) { message } }"#,
+            ))
+            .await;
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["collectHellos"],
+            serde_json::json!([{ "message": "Hello, Ada!" }, { "message": "Hello, World!" }])
+        );
+
+        let chunk = std::env::temp_dir().join(format!("upload-chunk-{}", std::process::id()));
+        std::fs::write(&chunk, b"abcd").expect("write chunk");
+        let mut request = async_graphql::Request::new(
+            "mutation ($data: [Upload!]!) { uploadChunks(data: $data) { size } }",
+        )
+        .variables(async_graphql::Variables::from_json(
+            serde_json::json!({ "data": [null, null] }),
+        ));
+        for idx in 0..2 {
+            request.set_upload(
+                &format!("variables.data.{idx}"),
+                UploadValue {
+                    filename: "chunk".to_string(),
+                    content_type: None,
+                    content: std::fs::File::open(&chunk).expect("open chunk"),
+                },
+            );
+        }
+        let response = schema.execute(request).await;
+        std::fs::remove_file(&chunk).ok();
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(data["uploadChunks"]["size"], "8");
+
+        let messages: Vec<_> = schema
+            .executor()
+            .execute_stream(async_graphql::Request::new(
+                r#"subscription { chat(name: ["Ada", "Grace"]) { message } }"#,
+            ))
+            .map(|response| {
+                assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+                response.data.into_json().expect("valid JSON response")["chat"]["message"].clone()
+            })
+            .collect()
+            .await;
+        assert_eq!(messages, ["Hello, Ada!", "Hello, Grace!"]);
+    }
+}
+
+/// A flat GraphQL argument and the request field path it populates.
+#[derive(Clone)]
+struct ArgumentBinding {
+    name: String,
+    path: Vec<FieldDescriptor>,
+}
+
+impl ArgumentBinding {
+    fn field(&self) -> &FieldDescriptor {
+        self.path.last().expect("argument path is never empty")
+    }
+
+    /// The dotted proto path of the bound field, relative to the request.
+    fn proto_path(&self) -> String {
+        self.path
+            .iter()
+            .map(FieldDescriptor::name)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+/// Map request fields to flat arguments.
+///
+/// Without plucks every non-omitted top-level field becomes an argument. With
+/// `request.plucks` only the listed fields are exposed; dotted paths such as
+/// `filter.status` lift a field of a nested request message to a top-level argument.
+fn argument_bindings(
+    input_desc: &MessageDescriptor,
+    plucks: &[String],
+    build_ctx: &BuildContext,
+) -> Result<Vec<ArgumentBinding>> {
+    if plucks.is_empty() {
+        return Ok(input_desc
+            .fields()
+            .filter(|field| !field_is_omitted(field, build_ctx))
+            .map(|field| ArgumentBinding {
+                name: graphql_field_name(&field, build_ctx),
+                path: vec![field],
+            })
+            .collect());
+    }
+
+    let mut bindings: Vec<ArgumentBinding> = Vec::with_capacity(plucks.len());
+    for pluck in plucks {
+        let mut desc = input_desc.clone();
+        let mut path = Vec::new();
+        let segments: Vec<&str> = pluck.split('.').collect();
+        for (idx, segment) in segments.iter().enumerate() {
+            let field = desc.get_field_by_name(segment).ok_or_else(|| {
+                Error::Schema(format!(
+                    "request pluck {pluck}: {} has no field {segment}",
+                    desc.full_name()
+                ))
+            })?;
+
+            if idx + 1 < segments.len() {
+                desc = match field.kind() {
+                    Kind::Message(msg) if !field.is_list() && !field.is_map() => msg,
+                    _ => {
+                        return Err(Error::Schema(format!(
+                            "request pluck {pluck}: {} is not a singular message field",
+                            field.full_name()
+                        )))
+                    }
+                };
+            }
+            path.push(field);
+        }
+
+        let name = graphql_field_name(path.last().expect("pluck has a segment"), build_ctx);
+        if bindings.iter().any(|binding| binding.name == name) {
+            return Err(Error::Schema(format!(
+                "request pluck {pluck} on {}: argument {name} is defined twice",
+                input_desc.full_name()
+            )));
+        }
+        bindings.push(ArgumentBinding { name, path });
+    }
+    Ok(bindings)
+}
+
+#[cfg(test)]
+mod pluck_tests {
+    use super::tests::{fixture_schema, spawn_fixtures, test_build_ctx, FIXTURES_DESCRIPTOR};
+    use super::*;
+
+    #[tokio::test]
+    async fn request_plucks_rebuild_nested_messages() {
+        let schema = fixture_schema(&["operations.Operations"], &spawn_fixtures().await);
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{ searchBooks(title: "Dune", limit: 2) { title } __type(name: "Query") { fields { name args { name } } } }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["searchBooks"],
+            serde_json::json!([{ "title": "Dune" }, { "title": "Dune" }])
+        );
+
+        let search = data["__type"]["fields"]
+            .as_array()
+            .expect("query fields")
+            .iter()
+            .find(|f| f["name"] == "searchBooks")
+            .expect("searchBooks field");
+        assert_eq!(
+            search["args"],
+            serde_json::json!([{ "name": "title" }, { "name": "limit" }])
+        );
+    }
+
+    #[test]
+    fn request_plucks_must_name_message_paths() {
+        let pool = DescriptorPool::decode(FIXTURES_DESCRIPTOR).expect("descriptor decodes");
+        let build_ctx = test_build_ctx(&pool);
+        let request = pool
+            .get_message_by_name("operations.SearchBooksRequest")
+            .expect("search request");
+
+        let bindings = argument_bindings(&request, &["filter.author".into()], &build_ctx)
+            .expect("nested pluck");
+        assert_eq!(bindings[0].name, "author");
+        assert_eq!(bindings[0].path.len(), 2);
+
+        assert!(argument_bindings(&request, &["limit.value".into()], &build_ctx).is_err());
+        assert!(argument_bindings(&request, &["filter.missing".into()], &build_ctx).is_err());
+        assert!(argument_bindings(
+            &request,
+            &["filter.title".into(), "filter.title".into()],
+            &build_ctx
+        )
+        .is_err());
+    }
+}
+
 fn build_arguments(
     input_desc: &MessageDescriptor,
     wrapper: &Option<String>,
-    field_ext: &ExtensionDescriptor,
+    bindings: &[ArgumentBinding],
+    build_ctx: &BuildContext,
     registry: &mut TypeRegistry,
-) -> Vec<ArgumentSpec> {
+) -> Result<Vec<ArgumentSpec>> {
     if let Some(wrapper_name) = wrapper {
-        let ty = registry.ensure_input_object(input_desc, field_ext);
-        return vec![ArgumentSpec {
+        let ty = registry.ensure_input_object(input_desc, build_ctx)?;
+        return Ok(vec![ArgumentSpec {
             name: wrapper_name.clone(),
             ty,
-        }];
+            default: None,
+            description: None,
+            deprecation: None,
+        }]);
     }
 
     let mut args = Vec::new();
-    for field in input_desc.fields() {
-        if field_is_omitted(&field, field_ext) {
-            continue;
-        }
-        let required = field_is_required(&field, field_ext);
-        let ty = registry.input_type_for_field(&field, field_ext, required);
-        let arg_name = graphql_field_name(&field, field_ext);
-        args.push(ArgumentSpec { name: arg_name, ty });
+    for binding in bindings {
+        let field = binding.field();
+        let required = field_is_required(field, build_ctx);
+        let ty = registry.input_type_for_field(field, build_ctx, required)?;
+        let default = if build_ctx.patch {
+            None
+        } else {
+            field_default(field, build_ctx)?
+        };
+        let description = registry.describe(&field.parent_file(), field.path());
+        // Only optional arguments may be deprecated.
+        let deprecation = if required {
+            None
+        } else {
+            registry.field_deprecation(field, build_ctx)
+        };
+        args.push(ArgumentSpec {
+            name: binding.name.clone(),
+            ty,
+            default,
+            description,
+            deprecation,
+        });
     }
-    args
+    Ok(args)
 }
 
-fn field_is_omitted(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> bool {
-    decode_extension::<GraphqlField>(&field.options(), field_ext)
+fn field_is_omitted(field: &FieldDescriptor, build_ctx: &BuildContext) -> bool {
+    decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
         .ok()
         .flatten()
         .map(|f| f.omit)
         .unwrap_or(false)
 }
 
-fn field_is_required(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> bool {
-    decode_extension::<GraphqlField>(&field.options(), field_ext)
+fn field_is_required(field: &FieldDescriptor, build_ctx: &BuildContext) -> bool {
+    decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
         .ok()
         .flatten()
         .map(|f| f.required)
         .unwrap_or(false)
+        || has_field_behavior(field, build_ctx, "REQUIRED")
+}
+
+/// Whether the `google.api.field_behavior` annotation of `field` lists `behavior`.
+fn has_field_behavior(field: &FieldDescriptor, build_ctx: &BuildContext, behavior: &str) -> bool {
+    let Some(ext) = &build_ctx.behavior else {
+        return false;
+    };
+    let Some(number) = ext
+        .kind()
+        .as_enum()
+        .and_then(|behaviors| behaviors.get_value_by_name(behavior))
+        .map(|value| value.number())
+    else {
+        return false;
+    };
+    let options = field.options();
+    options.has_extension(ext)
+        && matches!(
+            options.get_extension(ext).as_ref(),
+            Value::List(values) if values.contains(&Value::EnumNumber(number))
+        )
+}
+
+#[cfg(test)]
+mod field_behavior_tests {
+    use super::tests::{
+        fixture_schema, spawn_fixtures, test_build_ctx, type_fields, FIXTURES_DESCRIPTOR,
+    };
+    use super::*;
+
+    #[tokio::test]
+    async fn field_behavior_annotations_shape_the_schema() {
+        let schema = fixture_schema(&["annotations.Library"], &spawn_fixtures().await);
+        assert_eq!(
+            type_fields(&schema, "annotations_Book")
+                .await
+                .into_values()
+                .collect::<Vec<_>>(),
+            [
+                "create_time: String",
+                "isbn: String",
+                "name: String",
+                "note: String",
+                "shelf: String",
+                "title: String!"
+            ]
+        );
+        assert_eq!(
+            type_fields(&schema, "annotations_BookInput")
+                .await
+                .into_values()
+                .collect::<Vec<_>>(),
+            [
+                "import_token: String",
+                "isbn: String",
+                "name: String",
+                "note: String",
+                "shelf: String",
+                "title: String!"
+            ]
+        );
+        // Patches only carry the fields that change, so nothing in them is required, and
+        // IMMUTABLE fields cannot change at all.
+        assert_eq!(
+            type_fields(&schema, "annotations_BookPatchInput")
+                .await
+                .into_values()
+                .collect::<Vec<_>>(),
+            [
+                "import_token: String",
+                "name: String",
+                "note: String",
+                "shelf: String",
+                "title: String"
+            ]
+        );
+        assert_eq!(
+            type_fields(&schema, "Query").await["book"],
+            "book(name: String!): annotations_Book"
+        );
+        let mutation = type_fields(&schema, "Mutation").await;
+        assert_eq!(
+            mutation["createBook"],
+            "createBook(book: annotations_BookInput!): annotations_Book"
+        );
+        assert_eq!(
+            mutation["updateBook"],
+            "updateBook(book: annotations_BookPatchInput!): annotations_Book"
+        );
+
+        let update = |book: &str| {
+            schema.execute(async_graphql::Request::new(format!(
+                "mutation {{ updateBook(book: {book}) {{ name title note shelf }} }}"
+            )))
+        };
+        let rejected = update(r#"{ name: "books/1", isbn: "0441013597" }"#).await;
+        assert_eq!(
+            rejected.errors[0].message,
+            r#"Invalid value for argument "book", unknown field "isbn" of type "annotations_BookPatchInput""#
+        );
+        // The request check stays as a backstop for paths that reach the mask otherwise.
+        let descriptors = DescriptorPool::decode(FIXTURES_DESCRIPTOR).expect("decodes");
+        let err = reject_immutable_paths(
+            &descriptors
+                .get_message_by_name("annotations.UpdateBookRequest")
+                .expect("UpdateBookRequest"),
+            &BTreeSet::from(["book.isbn".to_string()]),
+            &test_build_ctx(&descriptors),
+        )
+        .expect_err("isbn is immutable");
+        assert_eq!(
+            err.message,
+            "annotations.Book.isbn is immutable and cannot be updated"
+        );
+        let accepted = update(r#"{ name: "books/1", title: "Dune" }"#).await;
+        assert!(accepted.errors.is_empty(), "errors: {:?}", accepted.errors);
+        // The backend echoes the patch it received: `shelf` keeps its proto default
+        // instead of `graphql.field.default`, as it is not in the mask.
+        assert_eq!(
+            accepted.data.into_json().expect("valid JSON response")["updateBook"],
+            serde_json::json!({
+                "name": "books/1",
+                "title": "Dune",
+                "note": "name,title",
+                "shelf": ""
+            })
+        );
+
+        // Client-streaming patches use the same patch input types.
+        let streamed = schema
+            .execute(async_graphql::Request::new(
+                r#"mutation {
+                    updateBooks(input: [
+                        { book: { name: "books/1", title: "Dune" } }
+                        { book: { name: "books/2", isbn: "0441013597" } }
+                    ]) { name }
+                }"#,
+            ))
+            .await;
+        assert!(
+            streamed.errors[0]
+                .message
+                .contains(r#"unknown field "isbn" of type "annotations_BookPatchInput""#),
+            "{:?}",
+            streamed.errors
+        );
+    }
 }
 
-fn graphql_field_name(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> String {
-    decode_extension::<GraphqlField>(&field.options(), field_ext)
+fn graphql_field_name(field: &FieldDescriptor, build_ctx: &BuildContext) -> String {
+    decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
         .ok()
         .flatten()
         .and_then(|f| {
@@ -907,19 +4908,19 @@ fn graphql_field_name(field: &FieldDescriptor, field_ext: &ExtensionDescriptor)
                 Some(f.name)
             }
         })
-        .unwrap_or_else(|| field.name().to_string())
+        .unwrap_or_else(|| build_ctx.naming.field_name(field))
 }
 
-fn field_is_external(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> bool {
-    decode_extension::<GraphqlField>(&field.options(), field_ext)
+fn field_is_external(field: &FieldDescriptor, build_ctx: &BuildContext) -> bool {
+    decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
         .ok()
         .flatten()
         .map(|f| f.external)
         .unwrap_or(false)
 }
 
-fn field_requires(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> Option<String> {
-    decode_extension::<GraphqlField>(&field.options(), field_ext)
+fn field_requires(field: &FieldDescriptor, build_ctx: &BuildContext) -> Option<String> {
+    decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
         .ok()
         .flatten()
         .and_then(|f| {
@@ -931,8 +4932,8 @@ fn field_requires(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> O
         })
 }
 
-fn field_provides(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> Option<String> {
-    decode_extension::<GraphqlField>(&field.options(), field_ext)
+fn field_provides(field: &FieldDescriptor, build_ctx: &BuildContext) -> Option<String> {
+    decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
         .ok()
         .flatten()
         .and_then(|f| {
@@ -944,21 +4945,270 @@ fn field_provides(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> O
         })
 }
 
-fn field_is_shareable(field: &FieldDescriptor, field_ext: &ExtensionDescriptor) -> bool {
-    decode_extension::<GraphqlField>(&field.options(), field_ext)
+fn field_is_shareable(field: &FieldDescriptor, build_ctx: &BuildContext) -> bool {
+    decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
         .ok()
         .flatten()
         .map(|f| f.shareable)
         .unwrap_or(false)
 }
 
+/// Read `graphql.field.default` as a GraphQL value, see [`parse_field_default`].
+fn field_default(field: &FieldDescriptor, build_ctx: &BuildContext) -> Result<Option<GqlValue>> {
+    let raw = decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)?
+        .map(|f| f.default)
+        .unwrap_or_default();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    parse_field_default(&raw, field, build_ctx).map(Some)
+}
+
+/// The default filled into outgoing requests for an absent `field`. Patches have none, as
+/// they only carry the fields listed in their update mask.
+fn request_default(
+    field: &FieldDescriptor,
+    build_ctx: &BuildContext,
+) -> async_graphql::Result<Option<GqlValue>> {
+    if build_ctx.patch {
+        return Ok(None);
+    }
+    Ok(field_default(field, build_ctx)?)
+}
+
+/// Parse a default value string according to the field's kind.
+///
+/// Scalars use their usual text form, enums their value name, and messages, repeated and
+/// map fields are given as JSON. The result is checked by converting it to the protobuf
+/// value it will populate.
+fn parse_field_default(
+    raw: &str,
+    field: &FieldDescriptor,
+    build_ctx: &BuildContext,
+) -> Result<GqlValue> {
+    let invalid = |reason: String| {
+        Error::Schema(format!(
+            "invalid default {raw:?} for {}: {reason}",
+            field.full_name()
+        ))
+    };
+    let from_json = || {
+        let json: serde_json::Value =
+            serde_json::from_str(raw).map_err(|e| invalid(e.to_string()))?;
+        GqlValue::from_json(json).map_err(|e| invalid(e.to_string()))
+    };
+
+    let value = if field.is_list() || field.is_map() {
+        from_json()?
+    } else {
+        match field.kind() {
+            Kind::Bool => GqlValue::Boolean(raw.parse().map_err(|e| invalid(format!("{e}")))?),
+            Kind::String | Kind::Bytes => GqlValue::String(raw.to_string()),
+            Kind::Float | Kind::Double => {
+                GqlValue::from(raw.parse::<f64>().map_err(|e| invalid(e.to_string()))?)
+            }
+            Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => {
+                GqlValue::from(raw.parse::<i32>().map_err(|e| invalid(e.to_string()))?)
+            }
+            Kind::Uint32 | Kind::Fixed32 => {
+                let v = raw.parse::<u32>().map_err(|e| invalid(e.to_string()))?;
+                GqlValue::from(uint32_to_graphql_int(v).map_err(invalid)?)
+            }
+            Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => GqlValue::String(
+                raw.parse::<i64>()
+                    .map_err(|e| invalid(e.to_string()))?
+                    .to_string(),
+            ),
+            Kind::Uint64 | Kind::Fixed64 => GqlValue::String(
+                raw.parse::<u64>()
+                    .map_err(|e| invalid(e.to_string()))?
+                    .to_string(),
+            ),
+            Kind::Enum(en) => {
+                // Accept the protobuf value name as well as the GraphQL one.
+                let value = en
+                    .get_value_by_name(raw)
+                    .or_else(|| enum_value_by_graphql_name(&en, raw, build_ctx))
+                    .ok_or_else(|| invalid(format!("unknown value of {}", en.full_name())))?;
+                GqlValue::Enum(Name::new(build_ctx.naming.enum_value_name(&value)))
+            }
+            Kind::Message(_) => from_json()?,
+        }
+    };
+
+    graphql_input_to_prost(&value, field, None, build_ctx).map_err(|e| invalid(e.message))?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod default_tests {
+    use super::tests::{fixture_schema, spawn_fixtures, test_build_ctx, FIXTURES_DESCRIPTOR};
+    use super::*;
+
+    #[tokio::test]
+    async fn field_defaults_apply_to_arguments_and_requests() {
+        let schema = fixture_schema(&["annotations.Printer"], &spawn_fixtures().await);
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{ print { text copies } __type(name: "Query") { fields { name args { name defaultValue } } } }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["print"],
+            serde_json::json!({ "text": "Hello, World!", "copies": "1" })
+        );
+
+        let print = data["__type"]["fields"]
+            .as_array()
+            .expect("query fields")
+            .iter()
+            .find(|f| f["name"] == "print")
+            .expect("print field");
+        assert_eq!(print["args"][0]["defaultValue"], "\"World\"");
+    }
+
+    #[test]
+    fn field_defaults_are_parsed_by_kind() {
+        let pool = DescriptorPool::decode(FIXTURES_DESCRIPTOR).expect("descriptor decodes");
+        let build_ctx = test_build_ctx(&pool);
+        let request = pool
+            .get_message_by_name("annotations.PrintRequest")
+            .expect("PrintRequest");
+        let field = |name| request.get_field_by_name(name).expect("request field");
+        let (copies, page_sizes, label) = (field("copies"), field("page_sizes"), field("label"));
+
+        assert_eq!(
+            parse_field_default("42", &copies, &build_ctx).expect("valid u64"),
+            GqlValue::String("42".into())
+        );
+        assert_eq!(
+            parse_field_default("[1, 2]", &page_sizes, &build_ctx).expect("valid list"),
+            GqlValue::from_json(serde_json::json!([1, 2])).unwrap()
+        );
+        assert!(parse_field_default(r#"{"labelKey": "abc"}"#, &label, &build_ctx).is_ok());
+
+        let err = parse_field_default("-1", &copies, &build_ctx).expect_err("negative u64");
+        assert!(
+            matches!(err, Error::Schema(ref msg) if msg.contains("annotations.PrintRequest.copies"))
+        );
+        assert!(parse_field_default("{not json", &label, &build_ctx).is_err());
+        assert!(parse_field_default(r#"{"labelKey": 1}"#, &label, &build_ctx).is_err());
+    }
+}
+
+fn field_resolver(field: &FieldDescriptor, build_ctx: &BuildContext) -> Option<String> {
+    decode_extension::<GraphqlField>(&field.options(), &build_ctx.field_ext)
+        .ok()
+        .flatten()
+        .and_then(|f| {
+            if f.resolver.is_empty() {
+                None
+            } else {
+                Some(f.resolver)
+            }
+        })
+}
+
+/// The oneof a field belongs to, ignoring the synthetic oneofs of proto3 `optional` fields.
+fn real_oneof(field: &FieldDescriptor) -> Option<OneofDescriptor> {
+    if field.field_descriptor_proto().proto3_optional() {
+        None
+    } else {
+        field.containing_oneof()
+    }
+}
+
+fn oneof_case_name(field: &FieldDescriptor) -> String {
+    field.name().to_ascii_uppercase()
+}
+
+/// Reject inputs that set more than one member of the same oneof; protobuf would
+/// otherwise keep only the last one.
+fn check_oneof_members<'a>(
+    fields: impl IntoIterator<Item = &'a FieldDescriptor>,
+) -> async_graphql::Result<()> {
+    let mut seen: HashMap<String, &FieldDescriptor> = HashMap::new();
+    for field in fields {
+        let Some(oneof) = real_oneof(field) else {
+            continue;
+        };
+        if let Some(previous) = seen.insert(oneof.full_name().to_string(), field) {
+            return Err(async_graphql::Error::new(format!(
+                "only one of {} and {} may be set (oneof {})",
+                previous.name(),
+                field.name(),
+                oneof.full_name()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod oneof_tests {
+    use super::tests::{fixture_schema, spawn_fixtures};
+
+    #[tokio::test]
+    async fn oneofs_map_to_one_of_inputs_and_case_fields() {
+        let schema = fixture_schema(&["types.Types"], &spawn_fixtures().await);
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{
+                    greet(target: { email: "ada@example.com" }, nickname: "Countess") {
+                        message style_case nickname formal
+                    }
+                    __type(name: "types_GreetTargetInput") { isOneOf }
+                }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(data["greet"]["message"], "Hello, ada@example.com!");
+        assert_eq!(data["greet"]["style_case"], "NICKNAME");
+        assert_eq!(data["greet"]["nickname"], "Countess");
+        assert_eq!(data["__type"]["isOneOf"], true);
+
+        let unset = schema
+            .execute(async_graphql::Request::new(r#"{ greet { style_case } }"#))
+            .await;
+        assert!(unset.errors.is_empty(), "errors: {:?}", unset.errors);
+        let data = unset.data.into_json().expect("valid JSON response");
+        assert_eq!(data["greet"]["style_case"], serde_json::Value::Null);
+
+        let both_members = schema
+            .execute(async_graphql::Request::new(
+                r#"{ greet(formal: true, nickname: "Countess") { message } }"#,
+            ))
+            .await;
+        assert_eq!(both_members.errors.len(), 1);
+        assert!(both_members.errors[0].message.contains("only one of"));
+
+        let both_targets = schema
+            .execute(async_graphql::Request::new(
+                r#"{ greet(target: { email: "a@b.c", user_id: "ada" }) { message } }"#,
+            ))
+            .await;
+        assert!(
+            !both_targets.errors.is_empty(),
+            "@oneOf input must reject two members"
+        );
+    }
+}
+
 fn compute_return_type(
     output_desc: &MessageDescriptor,
     response_opts: Option<&GraphqlResponse>,
-    field_ext: &ExtensionDescriptor,
+    connection: Option<&ConnectionPlan>,
+    build_ctx: &BuildContext,
     registry: &mut TypeRegistry,
     required: bool,
-) -> TypeRef {
+) -> Result<TypeRef> {
     let pluck_field = response_opts.and_then(|resp| {
         if resp.pluck.is_empty() {
             None
@@ -967,20 +5217,22 @@ fn compute_return_type(
         }
     });
 
-    let base = if let Some(field_name) = pluck_field {
+    let base = if let Some(plan) = connection {
+        registry.ensure_connection(&plan.items, build_ctx)?
+    } else if let Some(field_name) = pluck_field {
         if let Some(field_desc) = output_desc.get_field_by_name(field_name) {
-            registry.output_type_for_field(&field_desc, field_ext, false)
+            registry.output_type_for_field(&field_desc, build_ctx, false)?
         } else {
-            registry.ensure_object(output_desc, field_ext)
+            registry.well_known_type(output_desc, build_ctx, false)?
         }
     } else {
-        registry.ensure_object(output_desc, field_ext)
+        registry.well_known_type(output_desc, build_ctx, false)?
     };
 
     if required {
-        TypeRef::NonNull(Box::new(base))
+        Ok(TypeRef::NonNull(Box::new(base)))
     } else {
-        base
+        Ok(base)
     }
 }
 
@@ -988,7 +5240,8 @@ fn build_request_message(
     desc: &MessageDescriptor,
     ctx: &ResolverContext<'_>,
     wrapper: &Option<String>,
-    field_ext: &ExtensionDescriptor,
+    bindings: &[ArgumentBinding],
+    build_ctx: &BuildContext,
 ) -> async_graphql::Result<DynamicMessage> {
     if let Some(wrapper_name) = wrapper {
         let wrapper_value = ctx
@@ -996,7 +5249,7 @@ fn build_request_message(
             .get(wrapper_name)
             .ok_or_else(|| async_graphql::Error::new(format!("missing argument {wrapper_name}")))?;
         if let GqlValue::Object(obj) = wrapper_value.as_value() {
-            return object_to_message(desc, obj, ctx, field_ext);
+            return object_to_message(desc, obj, Some(ctx), build_ctx);
         } else {
             return Err(async_graphql::Error::new(format!(
                 "argument {wrapper_name} must be an object"
@@ -1004,46 +5257,191 @@ fn build_request_message(
         }
     }
 
+    check_oneof_members(
+        bindings
+            .iter()
+            .filter(|binding| {
+                ctx.args
+                    .get(&binding.name)
+                    .is_some_and(|value| !value.is_null())
+            })
+            .map(ArgumentBinding::field),
+    )?;
+
     let mut message = DynamicMessage::new(desc.clone());
-    for field in desc.fields() {
-        if field_is_omitted(&field, field_ext) {
+    for binding in bindings {
+        let field = binding.field();
+        let provided = ctx.args.get(&binding.name).is_some();
+        if !provided && binding.path.len() == 1 && oneof_is_set(&message, field) {
             continue;
         }
-        let arg_name = graphql_field_name(&field, field_ext);
-        if let Some(value) = ctx.args.get(&arg_name) {
-            let prost_value = graphql_input_to_prost(value.as_value(), &field, ctx, field_ext)?;
-            message.set_field(&field, prost_value);
+        let prost_value = if let Some(value) = ctx.args.get(&binding.name) {
+            if value.is_null() {
+                continue;
+            }
+            graphql_input_to_prost(value.as_value(), field, Some(ctx), build_ctx)?
+        } else if let Some(default) = request_default(field, build_ctx)? {
+            graphql_input_to_prost(&default, field, Some(ctx), build_ctx)?
+        } else {
+            continue;
+        };
+        if let Some(prost_value) = prost_value {
+            set_field_path(&mut message, &binding.path, prost_value);
         }
     }
     Ok(message)
 }
 
+/// Build the request messages of a client-streaming call, one per item of the list
+/// argument: whole messages under `wrapper`, or values of the single plucked field.
+fn build_request_stream(
+    desc: &MessageDescriptor,
+    ctx: &ResolverContext<'_>,
+    wrapper: &Option<String>,
+    bindings: &[ArgumentBinding],
+    update_mask: Option<&UpdateMaskPlan>,
+    build_ctx: &BuildContext,
+) -> async_graphql::Result<Vec<DynamicMessage>> {
+    let binding = match wrapper {
+        Some(_) => None,
+        None => Some(
+            bindings
+                .first()
+                .ok_or_else(|| async_graphql::Error::new("missing stream argument"))?,
+        ),
+    };
+    let name = wrapper
+        .as_deref()
+        .or(binding.map(|binding| binding.name.as_str()))
+        .unwrap_or_default();
+    let items = ctx
+        .args
+        .get(name)
+        .ok_or_else(|| async_graphql::Error::new(format!("missing argument {name}")))?
+        .list()?;
+
+    items
+        .iter()
+        .map(|item| {
+            let mut paths = BTreeSet::new();
+            let mut message = match binding {
+                Some(binding) => {
+                    let value = graphql_input_to_prost(
+                        item.as_value(),
+                        binding.field(),
+                        Some(ctx),
+                        build_ctx,
+                    )?;
+                    let mut message = DynamicMessage::new(desc.clone());
+                    if let Some(value) = value {
+                        set_field_path(&mut message, &binding.path, value);
+                    }
+                    input_field_path(
+                        binding.field(),
+                        item.as_value(),
+                        binding.proto_path(),
+                        build_ctx,
+                        &mut paths,
+                    );
+                    message
+                }
+                None => match item.as_value() {
+                    GqlValue::Object(obj) => {
+                        input_field_paths(desc, obj, None, build_ctx, &mut paths);
+                        object_to_message(desc, obj, Some(ctx), build_ctx)?
+                    }
+                    _ => {
+                        return Err(async_graphql::Error::new(format!(
+                            "items of argument {name} must be objects"
+                        )))
+                    }
+                },
+            };
+            // Each streamed request carries the mask of its own item.
+            if let Some(plan) = update_mask {
+                plan.apply(&mut message, paths, build_ctx)?;
+            }
+            Ok(message)
+        })
+        .collect()
+}
+
+/// Set a (possibly nested) field, creating intermediate messages along the path.
+fn set_field_path(message: &mut DynamicMessage, path: &[FieldDescriptor], value: Value) {
+    match path {
+        [] => {}
+        [field] => message.set_field(field, value),
+        [parent, rest @ ..] => {
+            if let Value::Message(child) = message.get_field_mut(parent) {
+                set_field_path(child, rest, value);
+            }
+        }
+    }
+}
+
 fn object_to_message(
     desc: &MessageDescriptor,
     values: &IndexMap<Name, GqlValue>,
-    ctx: &ResolverContext<'_>,
-    field_ext: &ExtensionDescriptor,
+    ctx: Option<&ResolverContext<'_>>,
+    build_ctx: &BuildContext,
 ) -> async_graphql::Result<DynamicMessage> {
+    let fields: Vec<_> = desc
+        .fields()
+        .filter(|field| !field_is_omitted(field, build_ctx))
+        .map(|field| {
+            let value = values.get(&Name::new(graphql_field_name(&field, build_ctx)));
+            (field, value)
+        })
+        .collect();
+    check_oneof_members(
+        fields
+            .iter()
+            .filter(|(_, value)| value.is_some_and(|v| *v != GqlValue::Null))
+            .map(|(field, _)| field),
+    )?;
+
+    // Absent fields get their defaults (except in patches), explicit `null`s stay cleared.
     let mut message = DynamicMessage::new(desc.clone());
-    for field in desc.fields() {
-        if field_is_omitted(&field, field_ext) {
+    for (field, value) in &fields {
+        if let Some(value) = value.filter(|value| **value != GqlValue::Null) {
+            if let Some(prost_value) = graphql_input_to_prost(value, field, ctx, build_ctx)? {
+                message.set_field(field, prost_value);
+            }
+        }
+    }
+    // Defaults must not displace a oneof member the client chose.
+    for (field, value) in &fields {
+        if value.is_some() || oneof_is_set(&message, field) {
             continue;
         }
-        let arg_name = graphql_field_name(&field, field_ext);
-        if let Some(value) = values.get(&Name::new(arg_name)) {
-            let prost_value = graphql_input_to_prost(value, &field, ctx, field_ext)?;
-            message.set_field(&field, prost_value);
+        if let Some(default) = request_default(field, build_ctx)? {
+            if let Some(prost_value) = graphql_input_to_prost(&default, field, ctx, build_ctx)? {
+                message.set_field(field, prost_value);
+            }
         }
     }
     Ok(message)
 }
 
+/// Whether another member of `field`'s oneof is already set on `message`.
+fn oneof_is_set(message: &DynamicMessage, field: &FieldDescriptor) -> bool {
+    real_oneof(field).is_some_and(|oneof| {
+        oneof
+            .fields()
+            .any(|member| member.number() != field.number() && message.has_field(&member))
+    })
+}
+
 fn graphql_input_to_prost(
     value: &GqlValue,
     field: &FieldDescriptor,
-    ctx: &ResolverContext<'_>,
-    field_ext: &ExtensionDescriptor,
-) -> async_graphql::Result<Value> {
+    ctx: Option<&ResolverContext<'_>>,
+    build_ctx: &BuildContext,
+) -> async_graphql::Result<Option<Value>> {
+    if field.is_map() {
+        return map_input_to_prost(value, field, ctx, build_ctx).map(Some);
+    }
+
     if field.is_list() {
         let list = match value {
             GqlValue::List(list) => list,
@@ -1052,22 +5450,170 @@ fn graphql_input_to_prost(
 
         let mut items = Vec::new();
         for v in list {
-            items.push(single_input_to_prost(v, field, ctx, field_ext)?);
+            items.extend(single_input_to_prost(v, field, ctx, build_ctx)?);
         }
-        return Ok(Value::List(items));
+        return Ok(Some(Value::List(items)));
     }
 
-    single_input_to_prost(value, field, ctx, field_ext)
+    single_input_to_prost(value, field, ctx, build_ctx)
 }
 
-fn single_input_to_prost(
+/// Convert a map input, accepting both the `[{ key, value }]` entry list and a JSON object.
+fn map_input_to_prost(
     value: &GqlValue,
     field: &FieldDescriptor,
-    ctx: &ResolverContext<'_>,
-    field_ext: &ExtensionDescriptor,
+    ctx: Option<&ResolverContext<'_>>,
+    build_ctx: &BuildContext,
 ) -> async_graphql::Result<Value> {
+    let Kind::Message(entry_desc) = field.kind() else {
+        return Err(async_graphql::Error::new("expected map"));
+    };
+    let key_field = entry_desc.map_entry_key_field();
+    let value_field = entry_desc.map_entry_value_field();
+
+    let mut map = HashMap::new();
+    match value {
+        GqlValue::List(entries) => {
+            let key_name = Name::new(graphql_field_name(&key_field, build_ctx));
+            let value_name = Name::new(graphql_field_name(&value_field, build_ctx));
+            for entry in entries {
+                let GqlValue::Object(entry) = entry else {
+                    return Err(async_graphql::Error::new("expected map entry object"));
+                };
+                let key = entry
+                    .get(&key_name)
+                    .ok_or_else(|| async_graphql::Error::new("map entry is missing its key"))?;
+                let key = single_input_to_prost(key, &key_field, ctx, build_ctx)?
+                    .and_then(Value::into_map_key)
+                    .ok_or_else(|| async_graphql::Error::new("invalid map key"))?;
+                let value = match entry.get(&value_name) {
+                    Some(value) => single_input_to_prost(value, &value_field, ctx, build_ctx)?
+                        .unwrap_or_else(|| Value::default_value_for_field(&value_field)),
+                    None => Value::default_value_for_field(&value_field),
+                };
+                map.insert(key, value);
+            }
+        }
+        GqlValue::Object(obj) => {
+            for (key, value) in obj {
+                let key = parse_map_key(key.as_str(), &key_field.kind())?;
+                let value = single_input_to_prost(value, &value_field, ctx, build_ctx)?
+                    .unwrap_or_else(|| Value::default_value_for_field(&value_field));
+                map.insert(key, value);
+            }
+        }
+        _ => return Err(async_graphql::Error::new("expected map entries or object")),
+    }
+    Ok(Value::Map(map))
+}
+
+fn parse_map_key(key: &str, kind: &Kind) -> async_graphql::Result<MapKey> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        async_graphql::Error::new(format!("invalid map key {key:?}: {e}"))
+    };
+    Ok(match kind {
+        Kind::String => MapKey::String(key.to_string()),
+        Kind::Bool => MapKey::Bool(key.parse().map_err(|e| invalid(&e))?),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => {
+            MapKey::I32(key.parse().map_err(|e| invalid(&e))?)
+        }
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => {
+            MapKey::I64(key.parse().map_err(|e| invalid(&e))?)
+        }
+        Kind::Uint32 | Kind::Fixed32 => MapKey::U32(key.parse().map_err(|e| invalid(&e))?),
+        Kind::Uint64 | Kind::Fixed64 => MapKey::U64(key.parse().map_err(|e| invalid(&e))?),
+        _ => return Err(invalid(&"unsupported key type")),
+    })
+}
+
+#[cfg(test)]
+mod map_tests {
+    use super::tests::{fixture_builder, fixture_schema, spawn_fixtures};
+    use super::*;
+
+    #[tokio::test]
+    async fn maps_round_trip_as_entry_lists() {
+        let schema = fixture_schema(&["types.Types"], &spawn_fixtures().await);
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{
+                    label(name: "Ada", labels: [{ key: "b", value: 2 }, { key: "a", value: 1 }]) {
+                        labels { key value }
+                    }
+                    __type(name: "types_Labeled") { fields { name type { kind ofType { kind name } } } }
+                }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["label"]["labels"],
+            serde_json::json!([{ "key": "a", "value": 1 }, { "key": "b", "value": 2 }])
+        );
+
+        let labels = data["__type"]["fields"]
+            .as_array()
+            .expect("fields")
+            .iter()
+            .find(|f| f["name"] == "labels")
+            .expect("labels field");
+        assert_eq!(labels["type"]["kind"], "LIST");
+        assert_eq!(labels["type"]["ofType"]["kind"], "NON_NULL");
+    }
+
+    #[tokio::test]
+    async fn maps_round_trip_as_json_scalar() {
+        let schema = fixture_builder(&["types.Types"])
+            .with_map_representation(MapRepresentation::Json)
+            .build(&spawn_fixtures().await)
+            .expect("schema builds");
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{
+                    label(name: "Ada", labels: { b: 2, a: 1 }) { labels }
+                    __type(name: "types_Labeled") { fields { name type { name } } }
+                }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["label"]["labels"],
+            serde_json::json!({ "a": 1, "b": 2 })
+        );
+
+        let labels = data["__type"]["fields"]
+            .as_array()
+            .expect("fields")
+            .iter()
+            .find(|f| f["name"] == "labels")
+            .expect("labels field");
+        assert_eq!(labels["type"]["name"], "JSON");
+
+        let invalid = schema
+            .execute(async_graphql::Request::new(
+                r#"{ label(name: "Ada", labels: { a: "one" }) { labels } }"#,
+            ))
+            .await;
+        assert!(
+            !invalid.errors.is_empty(),
+            "non-integer map value must be rejected"
+        );
+    }
+}
+
+fn single_input_to_prost(
+    value: &GqlValue,
+    field: &FieldDescriptor,
+    ctx: Option<&ResolverContext<'_>>,
+    build_ctx: &BuildContext,
+) -> async_graphql::Result<Option<Value>> {
     let kind = field.kind();
-    match kind {
+    let value = match kind {
         Kind::Bool => match value {
             GqlValue::Boolean(b) => Ok(Value::Bool(*b)),
             _ => Err(async_graphql::Error::new("expected boolean")),
@@ -1079,7 +5625,7 @@ fn single_input_to_prost(
         Kind::Bytes => match value {
             GqlValue::String(s) => {
                 if let Some(bytes) = upload_marker_to_bytes(ctx, s)? {
-                    return Ok(Value::Bytes(bytes.into()));
+                    return Ok(Some(Value::Bytes(bytes.into())));
                 }
 
                 BASE64
@@ -1092,51 +5638,55 @@ fn single_input_to_prost(
                 "expected upload or base64 string",
             )),
         },
-        Kind::Float | Kind::Double => match value {
+        Kind::Double => match value {
             GqlValue::Number(n) => n
                 .as_f64()
                 .map(Value::F64)
                 .ok_or_else(|| async_graphql::Error::new("expected float")),
             _ => Err(async_graphql::Error::new("expected float")),
         },
+        Kind::Float => match value {
+            GqlValue::Number(n) => {
+                let v = n
+                    .as_f64()
+                    .ok_or_else(|| async_graphql::Error::new("expected float"))?;
+                if v.is_finite() && v.abs() > f32::MAX as f64 {
+                    return Err(out_of_range(field, v));
+                }
+                Ok(Value::F32(v as f32))
+            }
+            _ => Err(async_graphql::Error::new("expected float")),
+        },
         Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => match value {
-            GqlValue::Number(n) => n
-                .as_i64()
-                .map(|v| Value::I32(v as i32))
-                .ok_or_else(|| async_graphql::Error::new("expected int")),
+            GqlValue::Number(n) => {
+                let v = n
+                    .as_i64()
+                    .ok_or_else(|| async_graphql::Error::new("expected int"))?;
+                i32::try_from(v)
+                    .map(Value::I32)
+                    .map_err(|_| out_of_range(field, v))
+            }
             _ => Err(async_graphql::Error::new("expected int")),
         },
         Kind::Uint32 | Kind::Fixed32 => match value {
-            GqlValue::Number(n) => n
-                .as_u64()
-                .map(|v| Value::U32(v as u32))
-                .ok_or_else(|| async_graphql::Error::new("expected unsigned int")),
+            GqlValue::Number(n) => {
+                let v = n
+                    .as_i64()
+                    .ok_or_else(|| async_graphql::Error::new("expected unsigned int"))?;
+                let v = u32::try_from(v).map_err(|_| out_of_range(field, v))?;
+                uint32_to_graphql_int(v).map_err(|e| {
+                    async_graphql::Error::new(format!("{e} (field {})", field.full_name()))
+                })?;
+                Ok(Value::U32(v))
+            }
             _ => Err(async_graphql::Error::new("expected unsigned int")),
         },
-        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => match value {
-            GqlValue::String(s) => s
-                .parse::<i64>()
-                .map(Value::I64)
-                .map_err(|e| async_graphql::Error::new(format!("invalid i64: {e}"))),
-            GqlValue::Number(n) => n
-                .as_i64()
-                .map(Value::I64)
-                .ok_or_else(|| async_graphql::Error::new("expected 64-bit int")),
-            _ => Err(async_graphql::Error::new("expected 64-bit int (as string)")),
-        },
-        Kind::Uint64 | Kind::Fixed64 => match value {
-            GqlValue::String(s) => s
-                .parse::<u64>()
-                .map(Value::U64)
-                .map_err(|e| async_graphql::Error::new(format!("invalid u64: {e}"))),
-            GqlValue::Number(n) => n
-                .as_u64()
-                .map(Value::U64)
-                .ok_or_else(|| async_graphql::Error::new("expected 64-bit uint")),
-            _ => Err(async_graphql::Error::new(
-                "expected 64-bit uint (as string)",
-            )),
-        },
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => int64_from_graphql::<i64>(value)
+            .map(Value::I64)
+            .ok_or_else(|| invalid_int64(field, value)),
+        Kind::Uint64 | Kind::Fixed64 => int64_from_graphql::<u64>(value)
+            .map(Value::U64)
+            .ok_or_else(|| invalid_int64(field, value)),
         Kind::Enum(en) => {
             let name_str = match value {
                 GqlValue::Enum(name) => Some(name.as_str()),
@@ -1145,30 +5695,168 @@ fn single_input_to_prost(
             }
             .ok_or_else(|| async_graphql::Error::new("expected enum value"))?;
 
-            let num = en
-                .get_value_by_name(name_str)
+            let num = enum_value_by_graphql_name(&en, name_str, build_ctx)
+                .or_else(|| en.get_value_by_name(name_str))
                 .map(|v| v.number())
                 .ok_or_else(|| async_graphql::Error::new("invalid enum value"))?;
             Ok(Value::EnumNumber(num))
         }
+        Kind::Message(msg) if WellKnownType::of(&msg).is_some() => {
+            return well_known_from_graphql(value, &msg).map(|message| message.map(Value::Message));
+        }
         Kind::Message(msg) => match value {
             GqlValue::Object(obj) => {
-                object_to_message(&msg, obj, ctx, field_ext).map(Value::Message)
+                object_to_message(&msg, obj, ctx, build_ctx).map(Value::Message)
             }
             _ => Err(async_graphql::Error::new("expected object")),
         },
+    };
+    value.map(Some)
+}
+
+/// Read a 64-bit integer from its string form or from an integral JSON number.
+fn int64_from_graphql<T>(value: &GqlValue) -> Option<T>
+where
+    T: std::str::FromStr + TryFrom<i64> + TryFrom<u64>,
+{
+    match value {
+        GqlValue::String(s) => s.parse().ok(),
+        GqlValue::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (_, Some(v)) => T::try_from(v).ok(),
+            (Some(v), None) => T::try_from(v).ok(),
+            (None, None) => None,
+        },
+        _ => None,
+    }
+}
+
+fn numeric_kind_name(field: &FieldDescriptor) -> &'static str {
+    match field.kind() {
+        Kind::Float => "float",
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => "int32",
+        Kind::Uint32 | Kind::Fixed32 => "uint32",
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => "int64",
+        Kind::Uint64 | Kind::Fixed64 => "uint64",
+        _ => "number",
+    }
+}
+
+fn out_of_range(field: &FieldDescriptor, value: impl std::fmt::Display) -> async_graphql::Error {
+    async_graphql::Error::new(format!(
+        "value {value} is out of range for {} field {}",
+        numeric_kind_name(field),
+        field.full_name()
+    ))
+}
+
+/// `uint32`/`fixed32` fields are declared as GraphQL `Int`, which is signed 32-bit, so input
+/// values above `i32::MAX` are rejected instead of breaking the declared type. Response values
+/// are passed through as they are, so backend data is never lost.
+fn uint32_to_graphql_int(value: u32) -> std::result::Result<i32, String> {
+    i32::try_from(value).map_err(|_| {
+        format!(
+            "uint32 value {value} does not fit GraphQL Int (at most {})",
+            i32::MAX
+        )
+    })
+}
+
+fn invalid_int64(field: &FieldDescriptor, value: &GqlValue) -> async_graphql::Error {
+    async_graphql::Error::new(format!(
+        "value {value} is not a valid {} for field {} (expected an in-range integer or numeric string)",
+        numeric_kind_name(field),
+        field.full_name()
+    ))
+}
+
+#[cfg(test)]
+mod number_tests {
+    use super::tests::{
+        fixture_schema, spawn_fixtures, test_build_ctx, type_fields, FIXTURES_DESCRIPTOR,
+    };
+    use super::*;
+
+    #[tokio::test]
+    async fn numeric_inputs_are_range_checked_and_64_bit_is_lossless() {
+        let pool = DescriptorPool::decode(FIXTURES_DESCRIPTOR).expect("descriptor decodes");
+        let build_ctx = test_build_ctx(&pool);
+        let counters = pool
+            .get_message_by_name("types.Counters")
+            .expect("Counters");
+        let field = |name| counters.get_field_by_name(name).expect("counter field");
+        let (delta, views, size) = (field("delta"), field("views"), field("size"));
+
+        let err = graphql_input_to_prost(&GqlValue::from(1_i64 << 40), &delta, None, &build_ctx)
+            .expect_err("int32 overflow");
+        assert!(
+            err.message.contains("out of range for int32"),
+            "{}",
+            err.message
+        );
+        assert!(graphql_input_to_prost(&GqlValue::from(-1), &size, None, &build_ctx).is_err());
+
+        let max = GqlValue::String(u64::MAX.to_string());
+        assert_eq!(
+            graphql_input_to_prost(&max, &size, None, &build_ctx).expect("u64::MAX"),
+            Some(Value::U64(u64::MAX))
+        );
+        assert_eq!(
+            prost_value_to_graphql(&Value::U64(u64::MAX), Some(&size), &build_ctx)
+                .expect("converts"),
+            max
+        );
+        assert_eq!(
+            prost_value_to_graphql(&Value::I64(i64::MIN), None, &build_ctx).expect("converts"),
+            GqlValue::String(i64::MIN.to_string())
+        );
+
+        let schema = fixture_schema(&["types.Types"], &spawn_fixtures().await);
+        let fields = type_fields(&schema, "types_Counters").await;
+        assert_eq!(fields["size"], "size: UInt64");
+        assert_eq!(fields["views"], "views: Int");
+
+        // uint32 fields are `Int`, so inputs above i32::MAX are rejected.
+        let response = schema
+            .execute(async_graphql::Request::new(
+                "{ counters(views: 2147483647) { views } }",
+            ))
+            .await;
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(data["counters"]["views"], i32::MAX);
+
+        let overflow = schema
+            .execute(async_graphql::Request::new(
+                "{ counters(views: 4000000000) { views } }",
+            ))
+            .await;
+        assert!(
+            overflow.errors[0]
+                .message
+                .contains("does not fit GraphQL Int"),
+            "{:?}",
+            overflow.errors
+        );
+
+        // Responses are never rejected: a backend value above i32::MAX is served as is.
+        assert_eq!(
+            prost_value_to_graphql(&Value::U32(u32::MAX), Some(&views), &build_ctx)
+                .expect("converts"),
+            GqlValue::from(u32::MAX)
+        );
     }
 }
 
 const UPLOAD_REF_PREFIX: &str = "#__graphql_file__:";
 
 fn upload_marker_to_bytes(
-    ctx: &ResolverContext<'_>,
+    ctx: Option<&ResolverContext<'_>>,
     marker: &str,
 ) -> async_graphql::Result<Option<Vec<u8>>> {
     let Some(rest) = marker.strip_prefix(UPLOAD_REF_PREFIX) else {
         return Ok(None);
     };
+    let ctx = ctx.ok_or_else(|| async_graphql::Error::new("uploads are not available here"))?;
 
     let idx = rest.parse::<usize>().map_err(|e| {
         async_graphql::Error::new(format!("invalid upload reference {marker}: {e}"))
@@ -1195,15 +5883,15 @@ fn upload_marker_to_bytes(
 fn prost_value_to_graphql(
     value: &Value,
     field: Option<&FieldDescriptor>,
-    field_ext: &ExtensionDescriptor,
+    build_ctx: &BuildContext,
 ) -> Result<GqlValue> {
     let kind = field.map(|f| f.kind());
     Ok(match value {
         Value::Bool(b) => GqlValue::Boolean(*b),
         Value::I32(v) => GqlValue::from(*v),
-        Value::I64(v) => GqlValue::from(*v),
-        Value::U32(v) => GqlValue::from(*v as i64),
-        Value::U64(v) => GqlValue::from(*v as i64),
+        Value::I64(v) => GqlValue::String(v.to_string()),
+        Value::U32(v) => GqlValue::from(*v),
+        Value::U64(v) => GqlValue::String(v.to_string()),
         Value::F32(v) => GqlValue::from(*v),
         Value::F64(v) => GqlValue::from(*v),
         Value::String(s) => GqlValue::from(s.clone()),
@@ -1211,7 +5899,7 @@ fn prost_value_to_graphql(
         Value::EnumNumber(num) => {
             if let Some(Kind::Enum(en)) = kind {
                 if let Some(val) = en.get_value(*num) {
-                    GqlValue::Enum(Name::new(val.name()))
+                    GqlValue::Enum(Name::new(build_ctx.naming.enum_value_name(&val)))
                 } else {
                     GqlValue::from(*num)
                 }
@@ -1219,22 +5907,34 @@ fn prost_value_to_graphql(
                 GqlValue::from(*num)
             }
         }
+        Value::Message(msg) if WellKnownType::of(&msg.descriptor()).is_some() => {
+            well_known_to_graphql(msg)?
+        }
         Value::Message(msg) => {
-            dynamic_message_to_value(msg, field_ext).unwrap_or_else(|_| GqlValue::Null)
+            dynamic_message_to_value(msg, build_ctx).unwrap_or_else(|_| GqlValue::Null)
         }
         Value::List(list) => {
             let items = list
                 .iter()
-                .map(|v| prost_value_to_graphql(v, field, field_ext))
+                .map(|v| prost_value_to_graphql(v, field, build_ctx))
                 .collect::<Result<Vec<_>>>()?;
             GqlValue::List(items)
         }
         Value::Map(map) => {
+            let value_field = match kind {
+                Some(Kind::Message(entry)) if entry.is_map_entry() => {
+                    Some(entry.map_entry_value_field())
+                }
+                _ => None,
+            };
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
             let mut obj = IndexMap::new();
-            for (k, v) in map {
+            for (k, v) in entries {
                 obj.insert(
                     Name::new(map_key_to_string(k)),
-                    prost_value_to_graphql(v, field, field_ext)?,
+                    prost_value_to_graphql(v, value_field.as_ref(), build_ctx)?,
                 );
             }
             GqlValue::Object(obj)
@@ -1244,16 +5944,16 @@ fn prost_value_to_graphql(
 
 fn dynamic_message_to_value(
     message: &DynamicMessage,
-    field_ext: &ExtensionDescriptor,
+    build_ctx: &BuildContext,
 ) -> Result<GqlValue> {
     let mut map = IndexMap::new();
     for field_desc in message.descriptor().fields() {
-        if field_is_omitted(&field_desc, field_ext) {
+        if field_is_omitted(&field_desc, build_ctx) {
             continue;
         }
         let value = message.get_field(&field_desc);
-        let gql_value = prost_value_to_graphql(&value, Some(&field_desc), field_ext)?;
-        let name = graphql_field_name(&field_desc, field_ext);
+        let gql_value = prost_value_to_graphql(&value, Some(&field_desc), build_ctx)?;
+        let name = graphql_field_name(&field_desc, build_ctx);
         map.insert(Name::new(name), gql_value);
     }
     Ok(GqlValue::Object(map))
@@ -1262,18 +5962,225 @@ fn dynamic_message_to_value(
 fn apply_response_pluck(
     response: &DynamicMessage,
     schema_opts: &GraphqlSchema,
-    field_ext: &ExtensionDescriptor,
-) -> Result<GqlValue> {
+    build_ctx: &BuildContext,
+    maps: MapRepresentation,
+) -> Result<FieldValue<'static>> {
     if let Some(resp_opts) = &schema_opts.response {
         if !resp_opts.pluck.is_empty() {
             if let Some(field_desc) = response.descriptor().get_field_by_name(&resp_opts.pluck) {
                 let val = response.get_field(&field_desc);
-                return prost_value_to_graphql(&val, Some(&field_desc), field_ext);
+                return prost_value_to_field_value(&val, &field_desc, build_ctx, maps);
             }
         }
     }
 
-    dynamic_message_to_value(response, field_ext)
+    message_to_field_value(response)
+}
+
+/// Convert a protobuf value into a [`FieldValue`], keeping messages as [`DynamicMessage`]
+/// so that object fields (including nested resolvers) can read the original message.
+fn prost_value_to_field_value(
+    value: &Value,
+    field: &FieldDescriptor,
+    build_ctx: &BuildContext,
+    maps: MapRepresentation,
+) -> Result<FieldValue<'static>> {
+    match value {
+        Value::Message(msg) => message_to_field_value(msg),
+        Value::List(items) => {
+            let items = items
+                .iter()
+                .map(|item| prost_value_to_field_value(item, field, build_ctx, maps))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(FieldValue::list(items))
+        }
+        Value::Map(map) if field.is_map() && maps == MapRepresentation::Entries => {
+            let Kind::Message(entry_desc) = field.kind() else {
+                return Err(Error::Schema(format!("{} is not a map", field.full_name())));
+            };
+            let key_field = entry_desc.map_entry_key_field();
+            let value_field = entry_desc.map_entry_value_field();
+
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            let items = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let mut entry = DynamicMessage::new(entry_desc.clone());
+                    entry.set_field(&key_field, Value::from(key.clone()));
+                    entry.set_field(&value_field, value.clone());
+                    FieldValue::owned_any(entry)
+                })
+                .collect::<Vec<_>>();
+            Ok(FieldValue::list(items))
+        }
+        _ => prost_value_to_graphql(value, Some(field), build_ctx).map(FieldValue::value),
+    }
+}
+
+/// Pass messages on as [`DynamicMessage`], except well-known types which are GraphQL scalars.
+fn message_to_field_value(message: &DynamicMessage) -> Result<FieldValue<'static>> {
+    if WellKnownType::of(&message.descriptor()).is_some() {
+        return well_known_to_graphql(message).map(FieldValue::value);
+    }
+    Ok(FieldValue::owned_any(message.clone()))
+}
+
+/// Protobuf well-known types that map to GraphQL scalars instead of objects.
+///
+/// Values use the canonical protobuf JSON mapping: timestamps are RFC 3339 strings,
+/// durations strings such as `"1.5s"`, wrappers their inner value (64-bit integers and
+/// bytes as strings), `Struct`/`Value`/`ListValue` plain JSON, and `Empty` is `true`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WellKnownType {
+    Timestamp,
+    Duration,
+    Wrapper,
+    Json,
+    Empty,
+}
+
+impl WellKnownType {
+    fn of(message: &MessageDescriptor) -> Option<Self> {
+        Some(match message.full_name() {
+            "google.protobuf.Timestamp" => Self::Timestamp,
+            "google.protobuf.Duration" => Self::Duration,
+            "google.protobuf.DoubleValue"
+            | "google.protobuf.FloatValue"
+            | "google.protobuf.Int64Value"
+            | "google.protobuf.UInt64Value"
+            | "google.protobuf.Int32Value"
+            | "google.protobuf.UInt32Value"
+            | "google.protobuf.BoolValue"
+            | "google.protobuf.StringValue"
+            | "google.protobuf.BytesValue" => Self::Wrapper,
+            "google.protobuf.Struct" | "google.protobuf.Value" | "google.protobuf.ListValue" => {
+                Self::Json
+            }
+            "google.protobuf.Empty" => Self::Empty,
+            _ => return None,
+        })
+    }
+}
+
+fn is_well_known_field(field: &FieldDescriptor) -> bool {
+    match field.kind() {
+        Kind::Message(msg) => !field.is_list() && WellKnownType::of(&msg).is_some(),
+        _ => false,
+    }
+}
+
+fn well_known_to_graphql(message: &DynamicMessage) -> Result<GqlValue> {
+    if WellKnownType::of(&message.descriptor()) == Some(WellKnownType::Empty) {
+        return Ok(GqlValue::Boolean(true));
+    }
+    let json = serde_json::to_value(message).map_err(|e| {
+        Error::Schema(format!(
+            "invalid {} value: {e}",
+            message.descriptor().full_name()
+        ))
+    })?;
+    GqlValue::from_json(json).map_err(|e| Error::Schema(e.to_string()))
+}
+
+/// Parse a well-known type from its scalar; `None` leaves the field unset, which is what
+/// `false` (or `null`) means for `Empty`.
+fn well_known_from_graphql(
+    value: &GqlValue,
+    desc: &MessageDescriptor,
+) -> async_graphql::Result<Option<DynamicMessage>> {
+    if WellKnownType::of(desc) == Some(WellKnownType::Empty) {
+        return match value {
+            GqlValue::Boolean(true) => Ok(Some(DynamicMessage::new(desc.clone()))),
+            GqlValue::Boolean(false) | GqlValue::Null => Ok(None),
+            _ => Err(async_graphql::Error::new("expected boolean")),
+        };
+    }
+    let json = value.clone().into_json()?;
+    DynamicMessage::deserialize(desc.clone(), json)
+        .map(Some)
+        .map_err(|e| async_graphql::Error::new(format!("invalid {}: {e}", desc.full_name())))
+}
+
+#[cfg(test)]
+mod well_known_tests {
+    use super::tests::{fixture_schema, spawn_fixtures};
+    use super::*;
+
+    #[tokio::test]
+    async fn well_known_types_map_to_scalars() {
+        let schema = fixture_schema(&["types.Types"], &spawn_fixtures().await);
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{
+                    echoStats(
+                        sent_at: "2024-05-06T07:08:09.5Z"
+                        ttl: "1.5s"
+                        priority: 3
+                        extra: { tags: ["a", "b"], nested: { ok: true } }
+                        ack: true
+                    ) { sent_at ttl priority note extra ack }
+                    __type(name: "types_Stats") { fields { name type { name } } }
+                }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["echoStats"],
+            serde_json::json!({
+                "sent_at": "2024-05-06T07:08:09.500Z",
+                "ttl": "1.500s",
+                "priority": 3,
+                "note": null,
+                "extra": { "tags": ["a", "b"], "nested": { "ok": true } },
+                "ack": true,
+            })
+        );
+
+        let types: HashMap<String, String> = data["__type"]["fields"]
+            .as_array()
+            .expect("fields")
+            .iter()
+            .map(|f| {
+                (
+                    f["name"].as_str().unwrap().to_string(),
+                    f["type"]["name"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(types["sent_at"], "DateTime");
+        assert_eq!(types["ttl"], "String");
+        assert_eq!(types["priority"], "Int");
+        assert_eq!(types["note"], "String");
+        assert_eq!(types["extra"], "JSON");
+        assert_eq!(types["ack"], "Boolean");
+
+        let unacked = schema
+            .execute(async_graphql::Request::new(
+                r#"{ echoStats(priority: 1, ack: false) { ack } }"#,
+            ))
+            .await;
+        assert!(unacked.errors.is_empty(), "errors: {:?}", unacked.errors);
+        let data = unacked.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["echoStats"]["ack"],
+            serde_json::Value::Null,
+            "`false` must leave the Empty field unset"
+        );
+
+        let invalid = schema
+            .execute(async_graphql::Request::new(
+                r#"{ echoStats(sent_at: "yesterday") { sent_at } }"#,
+            ))
+            .await;
+        assert!(
+            !invalid.errors.is_empty(),
+            "invalid timestamps must be rejected"
+        );
+    }
 }
 
 fn map_key_to_string(key: &MapKey) -> String {
@@ -1287,6 +6194,243 @@ fn map_key_to_string(key: &MapKey) -> String {
     }
 }
 
+/// Strip the space protoc keeps after `//` and surrounding blank lines.
+fn clean_comment(comment: &str) -> Option<String> {
+    let text = comment
+        .lines()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod comment_tests {
+    use super::tests::{fixture_builder, fixture_schema};
+    use super::*;
+
+    #[tokio::test]
+    async fn proto_comments_become_descriptions() {
+        let query = r#"{
+            __type(name: "annotations_HelloReply") { description fields { name description } }
+            __schema { queryType { fields { name description args { name description } } } }
+        }"#;
+
+        let schema = fixture_schema(&["annotations.Greeter"], &GrpcClientPool::new());
+        let data = schema
+            .execute(async_graphql::Request::new(query))
+            .await
+            .data
+            .into_json()
+            .expect("valid JSON response");
+
+        assert_eq!(data["__type"]["description"], "A rendered greeting.");
+        assert_eq!(
+            data["__type"]["fields"][0]["description"],
+            "The greeting text, e.g. \"Hello, World!\"."
+        );
+        assert_eq!(
+            data["__type"]["fields"][1]["description"],
+            serde_json::Value::Null
+        );
+        let fields = data["__schema"]["queryType"]["fields"]
+            .as_array()
+            .expect("query fields");
+        let hello = fields.iter().find(|f| f["name"] == "hello").expect("hello");
+        assert_eq!(hello["description"], "Greet a single person.");
+        assert_eq!(hello["args"][0]["description"], "Who to greet.");
+        let again = fields
+            .iter()
+            .find(|f| f["name"] == "helloAgain")
+            .expect("helloAgain");
+        // The service comment is not copied onto methods without a comment.
+        assert_eq!(again["description"], serde_json::Value::Null);
+
+        let schema = fixture_builder(&["annotations.Greeter"])
+            .with_descriptions(false)
+            .build(&GrpcClientPool::new())
+            .expect("schema builds");
+        let data = schema
+            .execute(async_graphql::Request::new(query))
+            .await
+            .data
+            .into_json()
+            .expect("valid JSON response");
+        assert_eq!(data["__type"]["description"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn deprecated_elements_carry_reasons() {
+        let schema = fixture_schema(&["annotations.Greeter"], &GrpcClientPool::new());
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{
+                    reply: __type(name: "annotations_HelloReply") {
+                        fields(includeDeprecated: true) { name deprecationReason }
+                    }
+                    query: __type(name: "Query") {
+                        fields(includeDeprecated: true) { name deprecationReason }
+                    }
+                    tone: __type(name: "annotations_Tone") {
+                        enumValues(includeDeprecated: true) { name deprecationReason }
+                    }
+                }"#,
+            ))
+            .await;
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        let reason = |ty: &str, list: &str, name: &str| {
+            data[ty][list]
+                .as_array()
+                .expect("introspected list")
+                .iter()
+                .find(|item| item["name"] == name)
+                .unwrap_or_else(|| panic!("{ty} has {name}"))["deprecationReason"]
+                .clone()
+        };
+
+        assert_eq!(
+            reason("reply", "fields", "legacy_message"),
+            "Use message instead."
+        );
+        assert_eq!(
+            reason("reply", "fields", "salutation"),
+            "No longer supported"
+        );
+        assert_eq!(
+            reason("reply", "fields", "message"),
+            serde_json::Value::Null
+        );
+        assert_eq!(
+            reason("query", "fields", "helloLater"),
+            "Greetings are no longer queued."
+        );
+        assert_eq!(reason("query", "fields", "hi"), "No longer supported");
+        assert_eq!(reason("query", "fields", "hello"), serde_json::Value::Null);
+        assert_eq!(reason("tone", "enumValues", "TONE_COLD"), "Too unfriendly.");
+        assert_eq!(
+            reason("tone", "enumValues", "TONE_WARM"),
+            serde_json::Value::Null
+        );
+    }
+}
+
+/// Everything needed to map protobuf fields to GraphQL and back: the `graphql.field`
+/// extension, `google.api.field_behavior` and the naming strategy.
+#[derive(Clone)]
+struct BuildContext {
+    field_ext: ExtensionDescriptor,
+    /// `google.api.field_behavior`, when the descriptors import it.
+    behavior: Option<ExtensionDescriptor>,
+    naming: Arc<dyn NamingStrategy>,
+    /// Whether input objects are built for an `update_mask` patch, which only carries the
+    /// fields that change: they get their own `Patch` types without required fields or
+    /// defaults.
+    patch: bool,
+}
+
+fn enum_value_by_graphql_name(
+    en: &EnumDescriptor,
+    name: &str,
+    build_ctx: &BuildContext,
+) -> Option<EnumValueDescriptor> {
+    en.values()
+        .find(|value| build_ctx.naming.enum_value_name(value) == name)
+}
+
+#[cfg(test)]
+mod naming_tests {
+    use super::tests::{fixture_builder, spawn_fixtures, test_build_ctx, FIXTURES_DESCRIPTOR};
+    use super::*;
+    use crate::naming::GraphqlNaming;
+
+    #[tokio::test]
+    async fn naming_strategy_renames_and_round_trips() {
+        let schema = fixture_builder(&["types.Types"])
+            .with_naming_strategy(GraphqlNaming::new())
+            .build(&spawn_fixtures().await)
+            .expect("schema builds");
+
+        let response = schema
+            .execute(async_graphql::Request::new(
+                r#"{
+                    greet(
+                        target: { userId: "ada" }
+                        nickname: "Countess"
+                        tone: WARM
+                        sentAt: "2024-01-02T03:04:05Z"
+                    ) { message styleCase tone sentAt }
+                    reply: __type(name: "GreetReply") { name }
+                    tone: __type(name: "Tone") { enumValues { name } }
+                    styleCase: __type(name: "GreetReplyStyleCase") { name }
+                }"#,
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "errors: {:?}", response.errors);
+        let data = response.data.into_json().expect("valid JSON response");
+        assert_eq!(
+            data["greet"],
+            serde_json::json!({
+                "message": "Hello, ada!",
+                "styleCase": "NICKNAME",
+                "tone": "WARM",
+                "sentAt": "2024-01-02T03:04:05Z",
+            })
+        );
+        assert_eq!(data["reply"]["name"], "GreetReply");
+        assert_eq!(
+            data["tone"]["enumValues"],
+            serde_json::json!([{ "name": "UNSPECIFIED" }, { "name": "WARM" }, { "name": "COLD" }])
+        );
+        assert_eq!(data["styleCase"]["name"], "GreetReplyStyleCase");
+
+        let descriptors = DescriptorPool::decode(FIXTURES_DESCRIPTOR).expect("descriptor decodes");
+        let build_ctx = BuildContext {
+            naming: Arc::new(GraphqlNaming::new()),
+            ..test_build_ctx(&descriptors)
+        };
+        let tone = descriptors
+            .get_message_by_name("types.GreetRequest")
+            .and_then(|m| m.get_field_by_name("tone"))
+            .expect("tone field");
+        let warm = GqlValue::Enum(Name::new("WARM"));
+        assert_eq!(
+            graphql_input_to_prost(&warm, &tone, None, &build_ctx).expect("converts"),
+            Some(Value::EnumNumber(1))
+        );
+        assert_eq!(
+            prost_value_to_graphql(&Value::EnumNumber(1), Some(&tone), &build_ctx)
+                .expect("converts"),
+            warm
+        );
+        assert_eq!(
+            parse_field_default("TONE_WARM", &tone, &build_ctx).expect("proto name"),
+            warm
+        );
+        assert!(parse_field_default("LOUD", &tone, &build_ctx).is_err());
+    }
+}
+
+/// Add a lazy client for `service` to the pool from its `graphql.service` host, unless the
+/// pool already has one.
+fn connect_service(
+    service: &ServiceDescriptor,
+    service_ext: &ExtensionDescriptor,
+    client_pool: &GrpcClientPool,
+) -> Result<()> {
+    let service_options =
+        decode_extension::<GraphqlService>(&service.options(), service_ext)?.unwrap_or_default();
+
+    if !service_options.host.is_empty() && client_pool.get(service.full_name()).is_none() {
+        let client =
+            GrpcClient::connect_lazy(service_options.host.clone(), service_options.insecure)?;
+        client_pool.add(service.full_name(), client);
+    }
+    Ok(())
+}
+
 fn decode_extension<T: Message + Default>(
     opts: &DynamicMessage,
     ext: &ExtensionDescriptor,
@@ -1351,9 +6495,9 @@ impl Decoder for ReflectDecoder {
         }
         let len = Buf::remaining(buf);
         let bytes = buf.copy_to_bytes(len);
-        Ok(DynamicMessage::decode(self.desc.clone(), bytes)
+        DynamicMessage::decode(self.desc.clone(), bytes)
             .map(Some)
-            .map_err(|e| Status::internal(format!("decode error: {e}")))?)
+            .map_err(|e| Status::internal(format!("decode error: {e}")))
     }
 }
 
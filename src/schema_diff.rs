@@ -0,0 +1,632 @@
+//! Breaking-change detection between two versions of the generated schema
+//!
+//! [`SchemaDiff`] compares the GraphQL surface of two schemas, usually built from an old
+//! and a new descriptor set, and classifies every difference by its impact on existing
+//! clients:
+//!
+//! - [`ChangeSeverity::Breaking`]: removed types, fields, arguments and enum values,
+//!   incompatible type changes (including inputs becoming non-null) and new required
+//!   arguments or input fields.
+//! - [`ChangeSeverity::Dangerous`]: existing operations stay valid but may behave
+//!   differently, e.g. new enum values, new optional inputs or changed defaults.
+//! - [`ChangeSeverity::Safe`]: additions, deprecations and outputs becoming non-null.
+//!
+//! The same check is available from the command line as `grpc-graphql-gateway diff`.
+
+use crate::error::{Error, Result};
+use crate::grpc_client::GrpcClientPool;
+use crate::schema::{DynamicSchema, SchemaBuilder};
+use async_graphql::parser::parse_schema;
+use async_graphql::parser::types::{
+    BaseType, ConstDirective, FieldDefinition, InputValueDefinition, Type, TypeDefinition,
+    TypeKind, TypeSystemDefinition,
+};
+use async_graphql::parser::Positioned;
+use async_graphql::Value;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Impact of a schema change on existing clients.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ChangeSeverity {
+    /// Existing operations may stop validating or receive data they cannot handle.
+    Breaking,
+    /// Existing operations keep validating but may behave differently.
+    Dangerous,
+    /// Existing operations are unaffected.
+    Safe,
+}
+
+impl fmt::Display for ChangeSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChangeSeverity::Breaking => "breaking",
+            ChangeSeverity::Dangerous => "dangerous",
+            ChangeSeverity::Safe => "safe",
+        })
+    }
+}
+
+/// A single difference between two schemas.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaChange {
+    pub severity: ChangeSeverity,
+    /// Schema coordinate of the changed element, e.g. `Product.sku`,
+    /// `Query.searchProducts(query:)` or `Availability.IN_STOCK`.
+    pub path: String,
+    /// What changed, e.g. ``type changed from `Int` to `Int64` ``.
+    pub message: String,
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// One side of a [`SchemaDiff::between`]: a schema to build, or its SDL.
+pub enum SchemaSource {
+    Builder(SchemaBuilder),
+    Sdl(String),
+}
+
+impl SchemaSource {
+    fn into_sdl(self, client_pool: &GrpcClientPool) -> Result<String> {
+        match self {
+            SchemaSource::Builder(builder) => Ok(builder.build(client_pool)?.sdl()),
+            SchemaSource::Sdl(sdl) => Ok(sdl),
+        }
+    }
+}
+
+impl From<SchemaBuilder> for SchemaSource {
+    fn from(builder: SchemaBuilder) -> Self {
+        SchemaSource::Builder(builder)
+    }
+}
+
+/// Classified differences between an old and a new schema.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use grpc_graphql_gateway::{SchemaBuilder, SchemaDiff};
+///
+/// # fn main() -> grpc_graphql_gateway::Result<()> {
+/// let diff = SchemaDiff::between(
+///     SchemaBuilder::new().with_descriptor_set_file("old_descriptor.bin")?,
+///     SchemaBuilder::new().with_descriptor_set_file("new_descriptor.bin")?,
+/// )?;
+/// for change in diff.breaking() {
+///     eprintln!("{change}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SchemaDiff {
+    changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    /// Build both schemas and compare them.
+    ///
+    /// Either side may also be SDL, e.g. a checked-in `schema.graphql` compared with the
+    /// schema built from a new descriptor set. Configure both builders the same way
+    /// (naming strategy, input suffix, ...) unless the configuration change itself is
+    /// what you want to check. Works with or without a running Tokio runtime.
+    pub fn between(old: impl Into<SchemaSource>, new: impl Into<SchemaSource>) -> Result<Self> {
+        // Hosts from `graphql.service` get lazy clients, which must be created inside a
+        // Tokio runtime even though nothing is ever called.
+        let runtime = match tokio::runtime::Handle::try_current() {
+            Ok(_) => None,
+            Err(_) => Some(tokio::runtime::Builder::new_current_thread().build()?),
+        };
+        let _guard = runtime.as_ref().map(|runtime| runtime.enter());
+
+        let client_pool = GrpcClientPool::new();
+        let old = old.into().into_sdl(&client_pool)?;
+        let new = new.into().into_sdl(&client_pool)?;
+        Self::from_sdl(&old, &new)
+    }
+
+    /// Compare two built schemas.
+    pub fn from_schemas(old: &DynamicSchema, new: &DynamicSchema) -> Result<Self> {
+        Self::from_sdl(&old.sdl(), &new.sdl())
+    }
+
+    /// Compare two schemas given as SDL, e.g. a checked-in `schema.graphql` against
+    /// [`DynamicSchema::sdl`].
+    pub fn from_sdl(old: &str, new: &str) -> Result<Self> {
+        let old = type_definitions(old)?;
+        let new = type_definitions(new)?;
+
+        let mut diff = Self::default();
+        for (name, old_ty) in &old {
+            match new.get(name) {
+                Some(new_ty) => diff.diff_type(name, old_ty, new_ty),
+                None => diff.push(
+                    ChangeSeverity::Breaking,
+                    name.clone(),
+                    format!("{} was removed", kind_name(&old_ty.kind)),
+                ),
+            }
+        }
+        for (name, new_ty) in &new {
+            if !old.contains_key(name) {
+                diff.push(
+                    ChangeSeverity::Safe,
+                    name.clone(),
+                    format!("{} was added", kind_name(&new_ty.kind)),
+                );
+            }
+        }
+        Ok(diff)
+    }
+
+    /// All changes: changes to existing types ordered by type name, then added types.
+    pub fn changes(&self) -> &[SchemaChange] {
+        &self.changes
+    }
+
+    /// Changes that can break existing clients.
+    pub fn breaking(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.with_severity(ChangeSeverity::Breaking)
+    }
+
+    /// Changes that keep existing operations valid but may change their behavior.
+    pub fn dangerous(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.with_severity(ChangeSeverity::Dangerous)
+    }
+
+    /// Changes that don't affect existing clients.
+    pub fn safe(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.with_severity(ChangeSeverity::Safe)
+    }
+
+    /// Whether any change is [`ChangeSeverity::Breaking`], i.e. may make existing operations
+    /// stop validating or receive data they cannot handle.
+    pub fn has_breaking_changes(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+
+    /// Whether the schemas are identical, with no change of any severity.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn with_severity(&self, severity: ChangeSeverity) -> impl Iterator<Item = &SchemaChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.severity == severity)
+    }
+
+    fn push(&mut self, severity: ChangeSeverity, path: String, message: String) {
+        self.changes.push(SchemaChange {
+            severity,
+            path,
+            message,
+        });
+    }
+
+    fn diff_type(&mut self, name: &str, old: &TypeDefinition, new: &TypeDefinition) {
+        self.diff_deprecation(name.to_string(), &old.directives, &new.directives);
+
+        match (&old.kind, &new.kind) {
+            (TypeKind::Scalar, TypeKind::Scalar) => {}
+            (TypeKind::Object(old), TypeKind::Object(new)) => {
+                self.diff_fields(name, &old.fields, &new.fields)
+            }
+            (TypeKind::Interface(old), TypeKind::Interface(new)) => {
+                self.diff_fields(name, &old.fields, &new.fields)
+            }
+            (TypeKind::InputObject(old), TypeKind::InputObject(new)) => {
+                self.diff_input_values(InputSite::InputField, name, &old.fields, &new.fields)
+            }
+            (TypeKind::Enum(old), TypeKind::Enum(new)) => {
+                for old_value in &old.values {
+                    let path = format!("{name}.{}", old_value.node.value.node);
+                    match new
+                        .values
+                        .iter()
+                        .find(|value| value.node.value.node == old_value.node.value.node)
+                    {
+                        Some(new_value) => self.diff_deprecation(
+                            path,
+                            &old_value.node.directives,
+                            &new_value.node.directives,
+                        ),
+                        None => self.push(
+                            ChangeSeverity::Breaking,
+                            path,
+                            "enum value was removed".into(),
+                        ),
+                    }
+                }
+                for new_value in &new.values {
+                    if !old
+                        .values
+                        .iter()
+                        .any(|value| value.node.value.node == new_value.node.value.node)
+                    {
+                        self.push(
+                            ChangeSeverity::Dangerous,
+                            format!("{name}.{}", new_value.node.value.node),
+                            "enum value was added".into(),
+                        );
+                    }
+                }
+            }
+            (TypeKind::Union(old), TypeKind::Union(new)) => {
+                for member in &old.members {
+                    if !new.members.iter().any(|m| m.node == member.node) {
+                        self.push(
+                            ChangeSeverity::Breaking,
+                            name.to_string(),
+                            format!("member `{}` was removed", member.node),
+                        );
+                    }
+                }
+                for member in &new.members {
+                    if !old.members.iter().any(|m| m.node == member.node) {
+                        self.push(
+                            ChangeSeverity::Dangerous,
+                            name.to_string(),
+                            format!("member `{}` was added", member.node),
+                        );
+                    }
+                }
+            }
+            (old, new) => self.push(
+                ChangeSeverity::Breaking,
+                name.to_string(),
+                format!("changed from {} to {}", kind_name(old), kind_name(new)),
+            ),
+        }
+    }
+
+    fn diff_fields(
+        &mut self,
+        type_name: &str,
+        old: &[Positioned<FieldDefinition>],
+        new: &[Positioned<FieldDefinition>],
+    ) {
+        for old_field in old {
+            let old_field = &old_field.node;
+            let path = format!("{type_name}.{}", old_field.name.node);
+            let Some(new_field) = new
+                .iter()
+                .map(|field| &field.node)
+                .find(|field| field.name.node == old_field.name.node)
+            else {
+                self.push(ChangeSeverity::Breaking, path, "field was removed".into());
+                continue;
+            };
+
+            let (old_ty, new_ty) = (&old_field.ty.node, &new_field.ty.node);
+            if old_ty != new_ty {
+                let severity = if is_safe_output_change(old_ty, new_ty) {
+                    ChangeSeverity::Safe
+                } else {
+                    ChangeSeverity::Breaking
+                };
+                self.push(
+                    severity,
+                    path.clone(),
+                    format!("type changed from `{old_ty}` to `{new_ty}`"),
+                );
+            }
+            self.diff_deprecation(path.clone(), &old_field.directives, &new_field.directives);
+            self.diff_input_values(
+                InputSite::Argument,
+                &path,
+                &old_field.arguments,
+                &new_field.arguments,
+            );
+        }
+
+        for new_field in new {
+            if !old
+                .iter()
+                .any(|field| field.node.name.node == new_field.node.name.node)
+            {
+                self.push(
+                    ChangeSeverity::Safe,
+                    format!("{type_name}.{}", new_field.node.name.node),
+                    "field was added".into(),
+                );
+            }
+        }
+    }
+
+    fn diff_input_values(
+        &mut self,
+        site: InputSite,
+        owner: &str,
+        old: &[Positioned<InputValueDefinition>],
+        new: &[Positioned<InputValueDefinition>],
+    ) {
+        let noun = site.noun();
+        for old_value in old {
+            let old_value = &old_value.node;
+            let path = site.path(owner, &old_value.name.node);
+            let Some(new_value) = new
+                .iter()
+                .map(|value| &value.node)
+                .find(|value| value.name.node == old_value.name.node)
+            else {
+                self.push(
+                    ChangeSeverity::Breaking,
+                    path,
+                    format!("{noun} was removed"),
+                );
+                continue;
+            };
+
+            let (old_ty, new_ty) = (&old_value.ty.node, &new_value.ty.node);
+            if old_ty != new_ty {
+                let severity = if is_safe_input_change(old_ty, new_ty) {
+                    ChangeSeverity::Safe
+                } else {
+                    ChangeSeverity::Breaking
+                };
+                self.push(
+                    severity,
+                    path.clone(),
+                    format!("type changed from `{old_ty}` to `{new_ty}`"),
+                );
+            }
+
+            let old_default = old_value.default_value.as_ref().map(|value| &value.node);
+            let new_default = new_value.default_value.as_ref().map(|value| &value.node);
+            if old_default != new_default {
+                // Dropping the default of a non-null input makes it required.
+                let severity = if new_default.is_none() && !new_ty.nullable {
+                    ChangeSeverity::Breaking
+                } else {
+                    ChangeSeverity::Dangerous
+                };
+                self.push(
+                    severity,
+                    path.clone(),
+                    format!(
+                        "default value changed from {} to {}",
+                        describe_default(old_default),
+                        describe_default(new_default)
+                    ),
+                );
+            }
+            self.diff_deprecation(path, &old_value.directives, &new_value.directives);
+        }
+
+        for new_value in new {
+            let new_value = &new_value.node;
+            if old
+                .iter()
+                .any(|value| value.node.name.node == new_value.name.node)
+            {
+                continue;
+            }
+            let path = site.path(owner, &new_value.name.node);
+            if new_value.ty.node.nullable || new_value.default_value.is_some() {
+                self.push(
+                    ChangeSeverity::Dangerous,
+                    path,
+                    format!("optional {noun} was added"),
+                );
+            } else {
+                self.push(
+                    ChangeSeverity::Breaking,
+                    path,
+                    format!("required {noun} was added"),
+                );
+            }
+        }
+    }
+
+    fn diff_deprecation(
+        &mut self,
+        path: String,
+        old: &[Positioned<ConstDirective>],
+        new: &[Positioned<ConstDirective>],
+    ) {
+        match (is_deprecated(old), is_deprecated(new)) {
+            (false, true) => self.push(ChangeSeverity::Safe, path, "was deprecated".into()),
+            (true, false) => {
+                self.push(ChangeSeverity::Safe, path, "is no longer deprecated".into())
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Where an input value is declared; decides its coordinate and wording.
+#[derive(Clone, Copy)]
+enum InputSite {
+    Argument,
+    InputField,
+}
+
+impl InputSite {
+    fn path(self, owner: &str, name: &str) -> String {
+        match self {
+            InputSite::Argument => format!("{owner}({name}:)"),
+            InputSite::InputField => format!("{owner}.{name}"),
+        }
+    }
+
+    fn noun(self) -> &'static str {
+        match self {
+            InputSite::Argument => "argument",
+            InputSite::InputField => "input field",
+        }
+    }
+}
+
+/// Parse SDL into its type definitions keyed by name, folding `extend` definitions into
+/// the type they extend.
+fn type_definitions(sdl: &str) -> Result<BTreeMap<String, TypeDefinition>> {
+    let document =
+        parse_schema(sdl).map_err(|err| Error::Schema(format!("failed to parse SDL: {err}")))?;
+
+    let mut types = BTreeMap::new();
+    for definition in document.definitions {
+        let TypeSystemDefinition::Type(ty) = definition else {
+            continue;
+        };
+        let ty = ty.node;
+        match types.entry(ty.name.node.to_string()) {
+            Entry::Vacant(entry) => {
+                entry.insert(ty);
+            }
+            Entry::Occupied(mut entry) => extend_type(&mut entry.get_mut().kind, ty.kind),
+        }
+    }
+    Ok(types)
+}
+
+fn extend_type(target: &mut TypeKind, extension: TypeKind) {
+    match (target, extension) {
+        (TypeKind::Object(target), TypeKind::Object(extension)) => {
+            target.fields.extend(extension.fields)
+        }
+        (TypeKind::Interface(target), TypeKind::Interface(extension)) => {
+            target.fields.extend(extension.fields)
+        }
+        (TypeKind::InputObject(target), TypeKind::InputObject(extension)) => {
+            target.fields.extend(extension.fields)
+        }
+        (TypeKind::Enum(target), TypeKind::Enum(extension)) => {
+            target.values.extend(extension.values)
+        }
+        (TypeKind::Union(target), TypeKind::Union(extension)) => {
+            target.members.extend(extension.members)
+        }
+        _ => {}
+    }
+}
+
+fn kind_name(kind: &TypeKind) -> &'static str {
+    match kind {
+        TypeKind::Scalar => "scalar",
+        TypeKind::Object(_) => "object type",
+        TypeKind::Interface(_) => "interface",
+        TypeKind::Union(_) => "union",
+        TypeKind::Enum(_) => "enum",
+        TypeKind::InputObject(_) => "input object",
+    }
+}
+
+fn is_deprecated(directives: &[Positioned<ConstDirective>]) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.node.name.node == "deprecated")
+}
+
+fn describe_default(value: Option<&Value>) -> String {
+    match value {
+        Some(value) => format!("`{value}`"),
+        None => "none".to_string(),
+    }
+}
+
+/// Outputs may become stricter: clients already handle every value of the new type.
+fn is_safe_output_change(old: &Type, new: &Type) -> bool {
+    (old.nullable || !new.nullable)
+        && match (&old.base, &new.base) {
+            (BaseType::Named(old), BaseType::Named(new)) => old == new,
+            (BaseType::List(old), BaseType::List(new)) => is_safe_output_change(old, new),
+            _ => false,
+        }
+}
+
+/// Inputs may become looser: every value clients send today is still accepted.
+fn is_safe_input_change(old: &Type, new: &Type) -> bool {
+    (new.nullable || !old.nullable)
+        && match (&old.base, &new.base) {
+            (BaseType::Named(old), BaseType::Named(new)) => old == new,
+            (BaseType::List(old), BaseType::List(new)) => is_safe_input_change(old, new),
+            _ => false,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG_V1: &[u8] = include_bytes!(concat!(
+        env!("OUT_DIR"),
+        "/compat_v1/catalog_descriptor.bin"
+    ));
+    const CATALOG_V2: &[u8] = include_bytes!(concat!(
+        env!("OUT_DIR"),
+        "/compat_v2/catalog_descriptor.bin"
+    ));
+
+    fn describe<'a>(changes: impl Iterator<Item = &'a SchemaChange>) -> Vec<String> {
+        changes.map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn classifies_changes_between_descriptor_sets() {
+        let diff = SchemaDiff::between(
+            SchemaBuilder::new().with_descriptor_set_bytes(CATALOG_V1),
+            SchemaBuilder::new().with_descriptor_set_bytes(CATALOG_V2),
+        )
+        .expect("diff");
+
+        assert_eq!(
+            describe(diff.breaking()),
+            [
+                "Query.searchProducts(query:): type changed from `String` to `String!`",
+                "catalog_Availability.DISCONTINUED: enum value was removed",
+                "catalog_Product.price_cents: type changed from `Int` to `Int64`",
+                "catalog_Product.sku: field was removed",
+                "catalog_ProductInput.price_cents: type changed from `Int` to `Int64`",
+                "catalog_ProductInput.sku: input field was removed",
+            ]
+        );
+        assert_eq!(
+            describe(diff.dangerous()),
+            [
+                "Query.searchProducts(offset:): optional argument was added",
+                "catalog_Availability.PREORDER: enum value was added",
+                "catalog_ProductInput.title: optional input field was added",
+            ]
+        );
+        assert_eq!(
+            describe(diff.safe()),
+            [
+                "Query.product: type changed from `catalog_Product` to `catalog_Product!`",
+                "catalog_Product.name: was deprecated",
+                "catalog_Product.title: field was added",
+                "catalog_ProductInput.name: was deprecated",
+                "Int64: scalar was added",
+            ]
+        );
+        assert!(diff.has_breaking_changes());
+
+        let unchanged = SchemaDiff::between(
+            SchemaBuilder::new().with_descriptor_set_bytes(CATALOG_V1),
+            SchemaBuilder::new().with_descriptor_set_bytes(CATALOG_V1),
+        )
+        .expect("diff");
+        assert!(unchanged.is_empty());
+    }
+
+    #[test]
+    fn nullability_rules_depend_on_direction() {
+        let ty = |s: &str| Type::new(s).expect("type");
+
+        assert!(is_safe_output_change(&ty("String"), &ty("String!")));
+        assert!(is_safe_output_change(&ty("[String]"), &ty("[String!]!")));
+        assert!(!is_safe_output_change(&ty("String!"), &ty("String")));
+        assert!(!is_safe_output_change(&ty("String"), &ty("[String]")));
+
+        assert!(is_safe_input_change(&ty("String!"), &ty("String")));
+        assert!(is_safe_input_change(&ty("[Int!]!"), &ty("[Int]")));
+        assert!(!is_safe_input_change(&ty("String"), &ty("String!")));
+        assert!(!is_safe_input_change(&ty("Int"), &ty("Int64")));
+    }
+}